@@ -0,0 +1,148 @@
+/*! Asynchronous client.
+
+`AsyncClient` mirrors `Client`, but returns futures driven by the
+asynchronous `reqwest` client. It is only available with the `async` feature.
+ */
+
+use super::{
+    build_stock_url, parse_stocks_response, RawResponse, Request, RequestOptions, Response,
+    StocksEndpoint, IEX_ENDPOINT,
+};
+use futures::Future;
+use reqwest::r#async;
+use std::sync::Arc;
+
+/// Future of the response of an `AsyncTransport`.
+pub type ResponseFuture = Box<dyn Future<Item = RawResponse, Error = failure::Error> + Send>;
+
+/// Asynchronous counterpart of `Transport`, issuing the requests of an
+/// `AsyncClient`.
+pub trait AsyncTransport: Send + Sync {
+    /// Performs the GET request `req`.
+    fn get(&self, req: &Request) -> ResponseFuture;
+}
+
+/// `AsyncTransport` backed by the asynchronous `reqwest::Client`.
+///
+/// The asynchronous reqwest requests have no timeout, so the `timeout` of
+/// requests is ignored.
+#[derive(Clone)]
+pub struct AsyncHttpTransport {
+    client: r#async::Client,
+}
+
+impl AsyncHttpTransport {
+    pub fn new() -> Self {
+        AsyncHttpTransport {
+            client: r#async::Client::new(),
+        }
+    }
+}
+
+impl Default for AsyncHttpTransport {
+    fn default() -> Self {
+        AsyncHttpTransport::new()
+    }
+}
+
+impl AsyncTransport for AsyncHttpTransport {
+    fn get(&self, req: &Request) -> ResponseFuture {
+        Box::new(
+            self.client
+                .get(&req.url)
+                .headers(req.headers.clone())
+                .send()
+                .and_then(|mut resp| {
+                    let status = resp.status().as_u16();
+                    resp.text().map(move |body| RawResponse { status, body })
+                })
+                .map_err(failure::Error::from),
+        )
+    }
+}
+
+/// `AsyncClient` acts as a Handler for the `Response` enum, without blocking.
+///
+/// Clones are cheap and share the transport, e.g. the connection pool of the
+/// `reqwest` client.
+#[derive(Clone)]
+pub struct AsyncClient {
+    transport: Arc<dyn AsyncTransport>,
+}
+
+impl Default for AsyncClient {
+    fn default() -> Self {
+        AsyncClient::new()
+    }
+}
+
+impl AsyncClient {
+    /// Create a new AsyncClient.
+    pub fn new() -> Self {
+        AsyncClient::with_transport(AsyncHttpTransport::new())
+    }
+
+    /// Create a new AsyncClient issuing its requests through `transport`.
+    pub fn with_transport<T>(transport: T) -> Self
+    where
+        T: AsyncTransport + 'static,
+    {
+        AsyncClient {
+            transport: Arc::new(transport),
+        }
+    }
+
+    /// stocks_request is the main entry-point to the IEX Stocks API.
+    pub fn stocks_request<S>(
+        &self,
+        symbol: S,
+        req: StocksEndpoint,
+    ) -> impl Future<Item = Response, Error = failure::Error>
+    where
+        S: Into<String>,
+    {
+        let symbol = symbol.into();
+        let url = build_stock_url(IEX_ENDPOINT, &symbol, &req, &RequestOptions::default());
+
+        self.transport
+            .get(&Request::new(url))
+            .and_then(move |raw| parse_stocks_response(symbol, &raw))
+    }
+}
+
+#[cfg(test)]
+impl AsyncTransport for super::MockTransport {
+    fn get(&self, req: &Request) -> ResponseFuture {
+        Box::new(futures::future::result(super::Transport::get(self, req)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::QUOTE;
+    use crate::{IexError, MockTransport, Quote};
+
+    #[test]
+    fn async_stocks_request() {
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let client = AsyncClient::with_transport(MockTransport::new().with(&url, QUOTE));
+
+        let quote: Quote = client
+            .stocks_request("aapl", StocksEndpoint::Quote)
+            .wait()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(quote.symbol, "AAPL");
+
+        let err = client
+            .stocks_request("zzzz", StocksEndpoint::Quote)
+            .wait()
+            .unwrap_err();
+        match err.downcast_ref::<IexError>() {
+            Some(IexError::NotFound { symbol }) => assert_eq!(symbol, "zzzz"),
+            _ => panic!("expected IexError::NotFound, got {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,467 @@
+/*! Batch Requests
+
+## HTTP request
+
+/stock/market/batch
+
+Returns multiple data types for up to 100 symbols in a single request.
+
+## Parameters
+
+| Parameter | Details |
+| symbols | • Required |
+| | • Comma delimited list of symbols limited to 100. |
+| types | • Required |
+| | • Comma delimited list of endpoints to call. The names should match the individual endpoint names. Limited to 10 endpoints. |
+| filter | • Optional |
+| | • Comma delimited list of fields to return, applied to every type. |
+
+## HTTP request example
+
+`GET /stock/market/batch?symbols=aapl,fb&types=quote&filter=symbol,latestPrice`
+
+```json
+{
+  "AAPL": { "quote": { "symbol": "AAPL", "latestPrice": 222.73 } },
+  "FB": { "quote": { "symbol": "FB", "latestPrice": 154.39 } }
+}
+```
+ */
+
+use super::types::Stats;
+#[cfg(feature = "blocking")]
+use super::Client;
+use super::{
+    normalize_symbols, Company, Duration, IexError, MyError, NewsCount, Quote, Response, Result,
+    IEX_ENDPOINT,
+};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Data types that can be requested in a batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BatchType {
+    Book,
+    Chart,
+    Company,
+    DelayedQuote,
+    Dividends,
+    Earnings,
+    EffectiveSpread,
+    Financials,
+    Logo,
+    News,
+    Ohlc,
+    Peers,
+    Previous,
+    Price,
+    Quote,
+    Relevant,
+    Splits,
+    Stats,
+    VolumeByVenue,
+}
+
+impl fmt::Display for BatchType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let token = match self {
+            BatchType::Book => "book",
+            BatchType::Chart => "chart",
+            BatchType::Company => "company",
+            BatchType::DelayedQuote => "delayed-quote",
+            BatchType::Dividends => "dividends",
+            BatchType::Earnings => "earnings",
+            BatchType::EffectiveSpread => "effective-spread",
+            BatchType::Financials => "financials",
+            BatchType::Logo => "logo",
+            BatchType::News => "news",
+            BatchType::Ohlc => "ohlc",
+            BatchType::Peers => "peers",
+            BatchType::Previous => "previous",
+            BatchType::Price => "price",
+            BatchType::Quote => "quote",
+            BatchType::Relevant => "relevant",
+            BatchType::Splits => "splits",
+            BatchType::Stats => "stats",
+            BatchType::VolumeByVenue => "volume-by-venue",
+        };
+        f.write_str(token)
+    }
+}
+
+/// Fields to return per requested type, e.g. only `symbol` and `latestPrice`
+/// for `BatchType::Quote`.
+///
+/// IEX only supports a single `filter` parameter, so the fields of all types
+/// are merged into one list which then applies to every type in the batch.
+pub type BatchFilters<'a> = HashMap<BatchType, Vec<&'a str>>;
+
+/// Builds the URL of a batch request for `types` over `symbols`.
+pub(crate) fn batch_url(symbols: &[&str], types: &[BatchType], filters: &BatchFilters) -> String {
+    let types_param: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+    let mut url = format!(
+        "{}/stock/market/batch?symbols={}&types={}",
+        IEX_ENDPOINT,
+        symbols.join(","),
+        types_param.join(",")
+    );
+
+    let mut fields: Vec<&str> = Vec::new();
+    for t in types {
+        for field in filters.get(t).into_iter().flatten() {
+            if !fields.contains(field) {
+                fields.push(field);
+            }
+        }
+    }
+    if !fields.is_empty() {
+        url.push_str("&filter=");
+        url.push_str(&fields.join(","));
+    }
+    url
+}
+
+/// Most symbols a batch request may ask for.
+pub const BATCH_MAX_SYMBOLS: usize = 100;
+
+/// Most types a batch request may ask for.
+pub const BATCH_MAX_TYPES: usize = 10;
+
+/// A batch request, composed fluently:
+///
+/// ```
+/// use iex::prelude::*;
+///
+/// let req = BatchRequest::new()
+///     .symbols(&["aapl", "fb"])
+///     .quote()
+///     .news(10)
+///     .chart(Duration::OneMonth)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchRequest {
+    symbols: Vec<String>,
+    types: Vec<BatchType>,
+    /// Chart range, e.g. `1m`.
+    range: Option<String>,
+    /// Number of news items.
+    last: Option<u32>,
+}
+
+impl BatchRequest {
+    pub fn new() -> Self {
+        BatchRequest::default()
+    }
+
+    /// Adds `symbols` to the request.
+    pub fn symbols(mut self, symbols: &[&str]) -> Self {
+        self.symbols.extend(symbols.iter().map(|s| s.to_string()));
+        self
+    }
+
+    /// Requests `t` for every symbol. Adding a type twice has no effect.
+    pub fn with_type(mut self, t: BatchType) -> Self {
+        if !self.types.contains(&t) {
+            self.types.push(t);
+        }
+        self
+    }
+
+    /// Requests the quotes.
+    pub fn quote(self) -> Self {
+        self.with_type(BatchType::Quote)
+    }
+
+    /// Requests the company profiles.
+    pub fn company(self) -> Self {
+        self.with_type(BatchType::Company)
+    }
+
+    /// Requests the key stats.
+    pub fn stats(self) -> Self {
+        self.with_type(BatchType::Stats)
+    }
+
+    /// Requests the latest `count` news items, capped to `NewsCount::MAX`.
+    pub fn news(mut self, count: u32) -> Self {
+        self.last = Some(NewsCount::clamped(count).get());
+        self.with_type(BatchType::News)
+    }
+
+    /// Requests the charts over `duration`.
+    pub fn chart(mut self, duration: Duration) -> Self {
+        self.range = Some(duration.to_string());
+        self.with_type(BatchType::Chart)
+    }
+
+    /// Checks the request against the IEX limits of 100 symbols and 10
+    /// types.
+    pub fn build(self) -> Result<BatchRequest> {
+        if self.symbols.is_empty() || self.symbols.len() > BATCH_MAX_SYMBOLS {
+            return Err(MyError::from("a batch request needs between 1 and 100 symbols").into());
+        }
+        if self.types.is_empty() || self.types.len() > BATCH_MAX_TYPES {
+            return Err(MyError::from("a batch request needs between 1 and 10 types").into());
+        }
+        Ok(self)
+    }
+
+    /// Returns the URL of the request.
+    pub fn url(&self) -> String {
+        let symbols: Vec<&str> = self.symbols.iter().map(|s| s.as_str()).collect();
+        let mut url = batch_url(&symbols, &self.types, &BatchFilters::new());
+        if let Some(ref range) = self.range {
+            url.push_str(&format!("&range={}", range));
+        }
+        if let Some(last) = self.last {
+            url.push_str(&format!("&last={}", last));
+        }
+        url
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Client {
+    /// Performs the batch request `req`.
+    ///
+    /// The response is an object keyed by symbol, each holding an object keyed
+    /// by type.
+    pub fn execute_batch(&self, req: &BatchRequest) -> Result<Response> {
+        self.get(&req.url())
+    }
+
+    /// Requests `types` for all `symbols` in a single call, optionally
+    /// restricting the returned fields with `filters`.
+    ///
+    /// The response is an object keyed by symbol, each holding an object keyed
+    /// by type.
+    pub fn batch(
+        &self,
+        symbols: &[&str],
+        types: &[BatchType],
+        filters: &BatchFilters,
+    ) -> Result<Response> {
+        self.get(&batch_url(symbols, types, filters))
+    }
+
+    /// Returns the quotes of `symbols`, keyed by uppercase symbol, using a
+    /// single batch request. See `SymbolLookup` to look them up in any
+    /// casing.
+    pub fn quotes(&self, symbols: &[&str]) -> Result<HashMap<String, Quote>> {
+        let symbols = normalize_symbols(symbols);
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        let resp = self.batch(&symbols, &[BatchType::Quote], &BatchFilters::new())?;
+        let entries: HashMap<String, QuoteEntry> = resp.try_into_with(self.strictness())?;
+        Ok(entries
+            .into_iter()
+            .map(|(k, v)| (k.to_uppercase(), v.quote))
+            .collect())
+    }
+
+    /// Returns the quote, key stats and company of `symbol`, using a single
+    /// batch request.
+    pub fn profile(&self, symbol: &str) -> Result<SymbolProfile> {
+        let types = [BatchType::Quote, BatchType::Stats, BatchType::Company];
+        let resp = self.batch(&[symbol], &types, &BatchFilters::new())?;
+        let entries: HashMap<String, SymbolProfile> = resp.try_into_with(self.strictness())?;
+        entries
+            .into_iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(symbol))
+            .map(|(_, profile)| profile)
+            .ok_or_else(|| {
+                IexError::NotFound {
+                    symbol: symbol.to_string(),
+                }
+                .into()
+            })
+    }
+}
+
+/// Quote, key stats and company of a symbol, see `Client::profile`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SymbolProfile {
+    pub quote: Quote,
+    pub stats: Stats,
+    pub company: Company,
+}
+
+/// Per-symbol object of a batch response requesting only quotes.
+#[derive(Serialize, Deserialize)]
+struct QuoteEntry {
+    quote: Quote,
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::{money, COMPANY, QUOTE, STATS};
+    use crate::{MockTransport, SymbolLookup};
+    use std::sync::Arc;
+
+    #[test]
+    fn batch_url_without_filter() {
+        let url = batch_url(
+            &["aapl", "fb"],
+            &[BatchType::Quote, BatchType::News],
+            &BatchFilters::new(),
+        );
+        assert_eq!(
+            url,
+            format!(
+                "{}/stock/market/batch?symbols=aapl,fb&types=quote,news",
+                IEX_ENDPOINT
+            )
+        );
+    }
+
+    #[test]
+    fn batch_url_with_filter() {
+        let mut filters = BatchFilters::new();
+        filters.insert(BatchType::Quote, vec!["symbol", "latestPrice"]);
+        filters.insert(BatchType::Company, vec!["symbol", "companyName"]);
+
+        let url = batch_url(
+            &["aapl", "fb"],
+            &[BatchType::Quote, BatchType::Company],
+            &filters,
+        );
+        assert_eq!(
+            url,
+            format!(
+                "{}/stock/market/batch?symbols=aapl,fb&types=quote,company&filter=symbol,latestPrice,companyName",
+                IEX_ENDPOINT
+            )
+        );
+    }
+
+    #[test]
+    fn batch_request_builder() {
+        let req = BatchRequest::new()
+            .symbols(&["aapl", "fb"])
+            .quote()
+            .news(10)
+            .chart(Duration::OneMonth)
+            .quote()
+            .build()
+            .unwrap();
+        assert_eq!(
+            req.url(),
+            format!(
+                "{}/stock/market/batch?symbols=aapl,fb&types=quote,news,chart&range=1m&last=10",
+                IEX_ENDPOINT
+            )
+        );
+    }
+
+    #[test]
+    fn batch_request_limits() {
+        assert!(BatchRequest::new().quote().build().is_err());
+        assert!(BatchRequest::new().symbols(&["aapl"]).build().is_err());
+
+        let symbols = vec!["aapl"; BATCH_MAX_SYMBOLS + 1];
+        assert!(BatchRequest::new()
+            .symbols(&symbols)
+            .quote()
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn client_execute_batch() {
+        let req = BatchRequest::new()
+            .symbols(&["aapl"])
+            .quote()
+            .build()
+            .unwrap();
+        let json_data = format!(r#"{{"AAPL":{{"quote":{}}}}}"#, QUOTE);
+        let client = Client::with_transport(MockTransport::new().with(&req.url(), &json_data));
+
+        let resp = client.execute_batch(&req).unwrap();
+        assert_eq!(resp.as_value()["AAPL"]["quote"]["symbol"], "AAPL");
+    }
+
+    #[test]
+    fn client_quotes() {
+        let url = batch_url(&["AAPL"], &[BatchType::Quote], &BatchFilters::new());
+        let json_data = format!(r#"{{"AAPL":{{"quote":{}}}}}"#, QUOTE);
+        let client = Client::with_transport(MockTransport::new().with(&url, &json_data));
+
+        let quotes = client.quotes(&["AAPL", "aapl"]).unwrap();
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes["AAPL"].latest_price, money("222.73"));
+        assert_eq!(
+            quotes.get_symbol("aapl").unwrap().latest_price,
+            money("222.73")
+        );
+    }
+
+    #[test]
+    fn client_profile() {
+        let types = [BatchType::Quote, BatchType::Stats, BatchType::Company];
+        let url = batch_url(&["aapl"], &types, &BatchFilters::new());
+        let json_data = format!(
+            r#"{{"AAPL":{{"quote":{},"stats":{},"company":{}}}}}"#,
+            QUOTE, STATS, COMPANY
+        );
+        let client = Client::with_transport(MockTransport::new().with(&url, &json_data));
+
+        let profile = client.profile("aapl").unwrap();
+        assert_eq!(profile.quote.latest_price, money("222.73"));
+        assert_eq!(profile.stats.beta, 1.15);
+        assert_eq!(profile.company.sector, "Technology");
+
+        let url = batch_url(&["zzzz"], &types, &BatchFilters::new());
+        let client = Client::with_transport(MockTransport::new().with(&url, "{}"));
+        assert!(client.profile("zzzz").is_err());
+    }
+
+    #[test]
+    fn strict_quotes_and_profile() {
+        let strict = |transport| {
+            crate::ClientBuilder::new()
+                .transport(transport)
+                .strictness(crate::Strictness::Strict)
+                .build()
+        };
+
+        let types = [BatchType::Quote, BatchType::Stats, BatchType::Company];
+        let url = batch_url(&["aapl"], &types, &BatchFilters::new());
+        let json_data = format!(
+            r#"{{"AAPL":{{"quote":{},"stats":{},"company":{}}}}}"#,
+            QUOTE, STATS, COMPANY
+        );
+        let client = strict(Arc::new(MockTransport::new().with(&url, &json_data)));
+        assert!(client.profile("aapl").is_ok());
+
+        let url = batch_url(&["AAPL"], &[BatchType::Quote], &BatchFilters::new());
+        let quote = QUOTE.replacen('{', r#"{"newUndocumentedField":1,"#, 1);
+        let json_data = format!(r#"{{"AAPL":{{"quote":{}}}}}"#, quote);
+        let transport = Arc::new(MockTransport::new().with(&url, &json_data));
+        assert!(Client::with_transport(transport.clone())
+            .quotes(&["aapl"])
+            .is_ok());
+        let err = strict(transport).quotes(&["aapl"]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unknown field `AAPL.quote.newUndocumentedField`"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn client_batch() {
+        let mut filters = BatchFilters::new();
+        filters.insert(BatchType::Quote, vec!["symbol", "latestPrice"]);
+        let url = batch_url(&["aapl"], &[BatchType::Quote], &filters);
+        let json_data = r#"{"AAPL":{"quote":{"symbol":"AAPL","latestPrice":222.73}}}"#;
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let resp = client
+            .batch(&["aapl"], &[BatchType::Quote], &filters)
+            .unwrap();
+        assert_eq!(resp.as_value()["AAPL"]["quote"]["latestPrice"], 222.73);
+    }
+}
@@ -38,8 +38,8 @@ json = JSON.parse(open(ARGV.shift).read)
 if json.is_a? Array
 	parse_object("ArrayInnerObjectRenameMe", json.shift)
 	TYPES.each do |name, definition|
-		puts "#[serde(rename_all = \"camelCase\")]"
 		puts "#[derive(Serialize, Deserialize, Debug)]"
+		puts "#[serde(rename_all = \"camelCase\")]"
 		puts "pub struct " + name + " {"
 
 		definition.each do |field_name, type|
@@ -52,8 +52,8 @@ if json.is_a? Array
 else
 	parse_object("NewTypeRenameMe", json)
 	TYPES.each do |name, definition|
-		puts "#[serde(rename_all = \"camelCase\")]"
 		puts "#[derive(Serialize, Deserialize, Debug)]"
+		puts "#[serde(rename_all = \"camelCase\")]"
 		puts "pub struct " + name + " {"
 
 		definition.each do |field_name, type|
@@ -1,11 +1,9 @@
 extern crate iex;
 extern crate serde_json;
-use iex::Book;
-use iex::{Client, StocksEndpoint};
+use iex::Client;
 
 fn main() {
     let client = Client::new();
-    let resp = client.stocks_request("aapl", StocksEndpoint::Book).unwrap();
-    let book = resp.try_into::<Book>();
+    let book = client.book("aapl");
     println!("{:?}", &book);
 }
@@ -0,0 +1,79 @@
+/*! Concurrent requests
+
+Helpers for the client methods which fetch several endpoints at once on
+scoped threads.
+ */
+
+use super::{MyError, Result};
+use std::thread;
+
+/// Waits for `handle`, turning a panic of its thread into an error.
+pub(crate) fn join<T>(handle: thread::ScopedJoinHandle<'_, Result<T>>) -> Result<T> {
+    handle
+        .join()
+        .unwrap_or_else(|_| Err(MyError::from("request thread panicked").into()))
+}
+
+/// Calls `f` with each of `items` on scoped threads, at most `limit` at a
+/// time, and returns the results in the order of `items`. A panic of `f` is
+/// returned as an error, like `join`.
+pub(crate) fn map_concurrent<I, T, F>(items: &[I], limit: usize, f: F) -> Vec<Result<T>>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> Result<T> + Sync,
+{
+    let f = &f;
+    let mut results = Vec::with_capacity(items.len());
+    for chunk in items.chunks(limit.max(1)) {
+        thread::scope(|s| {
+            let handles: Vec<_> = chunk.iter().map(|item| s.spawn(move || f(item))).collect();
+            results.extend(handles.into_iter().map(join));
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn results_keep_the_order_of_items() {
+        let results = map_concurrent(&[3, 1, 2], 2, |&n| {
+            thread::sleep(Duration::from_millis(n * 5));
+            Ok(n)
+        });
+        let results: Vec<u64> = results.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(results, [3, 1, 2]);
+    }
+
+    #[test]
+    fn at_most_limit_items_run_at_once() {
+        let (running, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let results = map_concurrent(&[0; 10], 3, |_| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        });
+        assert_eq!(results.len(), 10);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        let results = map_concurrent(&[1, 2], 2, |&n| {
+            if n == 2 {
+                panic!("boom");
+            }
+            Ok(n)
+        });
+        assert_eq!(results[0].as_ref().unwrap(), &1);
+        let err = results[1].as_ref().unwrap_err();
+        assert_eq!(err.to_string(), "request thread panicked");
+    }
+}
@@ -0,0 +1,435 @@
+/*! DEEP socket.io channel
+
+Encodes the socket.io `subscribe`/`unsubscribe` emits of the DEEP channel and
+decodes the frames it sends back into `DeepMessage`s. With the `websocket`
+feature, `DeepStream` manages the connection; otherwise the frames can be
+exchanged over a WebSocket opened by the caller.
+
+A `BookBuilder` maintains a live order book from the price level updates of
+the feed.
+ */
+
+use super::{AuctionData, BookLevel, IexError, Result, SystemEvent, Trade, TradingStatus};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// socket.io namespace of the DEEP channel.
+pub const DEEP_NAMESPACE: &str = "/1.0/deep";
+
+/// Order book update of a DEEP `book` message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepBook {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+/// Message received on the DEEP channel.
+#[derive(Debug)]
+pub enum DeepMessage {
+    Trade {
+        symbol: String,
+        trade: Trade,
+    },
+    Book {
+        symbol: String,
+        book: DeepBook,
+    },
+    Auction {
+        symbol: String,
+        auction: AuctionData,
+    },
+    SystemEvent(SystemEvent),
+    TradingStatus {
+        symbol: String,
+        status: TradingStatus,
+    },
+    /// Message type this crate doesn't decode, e.g. `ssr`.
+    Other {
+        symbol: String,
+        message_type: String,
+        data: Value,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    #[serde(default)]
+    symbol: String,
+    message_type: String,
+    data: Value,
+}
+
+impl DeepMessage {
+    /// Decodes a socket.io frame. Returns `None` for frames which aren't
+    /// `message` events, e.g. pings or the connect acknowledgement.
+    pub fn from_frame(frame: &str) -> Result<Option<DeepMessage>> {
+        let payload = match event_payload(frame) {
+            Some(payload) => payload,
+            None => return Ok(None),
+        };
+        let event: Vec<Value> = serde_json::from_str(payload).map_err(IexError::from)?;
+        match (event.first().and_then(Value::as_str), event.get(1)) {
+            (Some("message"), Some(Value::String(body))) => Self::from_json(body).map(Some),
+            (Some("message"), Some(body)) => Self::from_json(&body.to_string()).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Decodes the JSON body of a `message` event.
+    pub fn from_json(body: &str) -> Result<DeepMessage> {
+        let env: Envelope = serde_json::from_str(body).map_err(IexError::from)?;
+        let symbol = env.symbol;
+        let msg = match env.message_type.as_str() {
+            "trades" => DeepMessage::Trade {
+                symbol,
+                trade: serde_json::from_value(env.data).map_err(IexError::from)?,
+            },
+            "book" => DeepMessage::Book {
+                symbol,
+                book: serde_json::from_value(env.data).map_err(IexError::from)?,
+            },
+            "auction" => DeepMessage::Auction {
+                symbol,
+                auction: serde_json::from_value(env.data).map_err(IexError::from)?,
+            },
+            "systemevent" => {
+                DeepMessage::SystemEvent(serde_json::from_value(env.data).map_err(IexError::from)?)
+            }
+            "tradingstatus" => DeepMessage::TradingStatus {
+                symbol,
+                status: serde_json::from_value(env.data).map_err(IexError::from)?,
+            },
+            _ => DeepMessage::Other {
+                symbol,
+                message_type: env.message_type,
+                data: env.data,
+            },
+        };
+        Ok(msg)
+    }
+}
+
+/// Side of the order book.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// Change of a price level of the order book, see `BookBuilder::apply`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BookUpdate {
+    /// Adds `level`, or replaces the level at its price. A level with a size
+    /// of zero removes the level at its price, as DEEP sends for levels
+    /// emptied by trades and cancels.
+    Set { side: BookSide, level: BookLevel },
+    /// Removes the level at `price`, if any.
+    Delete { side: BookSide, price: f64 },
+}
+
+/// Order book maintained from incremental price level updates, e.g. decoded
+/// from the DEEP feed. Bids are kept from the highest price down and asks
+/// from the lowest price up, so the best prices come first.
+#[derive(Clone, Debug, Default)]
+pub struct BookBuilder {
+    bids: Vec<BookLevel>,
+    asks: Vec<BookLevel>,
+}
+
+impl BookBuilder {
+    /// Returns an empty book.
+    pub fn new() -> Self {
+        BookBuilder::default()
+    }
+
+    /// Returns a book starting from `book`, e.g. a full `book` message.
+    pub fn from_snapshot(book: DeepBook) -> Self {
+        let mut builder = BookBuilder::new();
+        for level in book.bids {
+            builder.apply(BookUpdate::Set {
+                side: BookSide::Bid,
+                level,
+            });
+        }
+        for level in book.asks {
+            builder.apply(BookUpdate::Set {
+                side: BookSide::Ask,
+                level,
+            });
+        }
+        builder
+    }
+
+    /// Applies `update` to the book.
+    pub fn apply(&mut self, update: BookUpdate) {
+        let (side, price, level) = match update {
+            BookUpdate::Set { side, level } if level.size > 0.0 => (side, level.price, Some(level)),
+            BookUpdate::Set { side, level } => (side, level.price, None),
+            BookUpdate::Delete { side, price } => (side, price, None),
+        };
+        let levels = match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        };
+        let position = levels.binary_search_by(|l| {
+            let ordering = l.price.partial_cmp(&price).unwrap_or(Ordering::Equal);
+            match side {
+                BookSide::Bid => ordering.reverse(),
+                BookSide::Ask => ordering,
+            }
+        });
+        match (position, level) {
+            (Ok(i), Some(level)) => levels[i] = level,
+            (Ok(i), None) => {
+                levels.remove(i);
+            }
+            (Err(i), Some(level)) => levels.insert(i, level),
+            (Err(_), None) => {}
+        }
+    }
+
+    /// Returns the current book, best prices first.
+    pub fn snapshot(&self) -> DeepBook {
+        DeepBook {
+            bids: self.bids.clone(),
+            asks: self.asks.clone(),
+        }
+    }
+}
+
+/// Returns the socket.io frame subscribing to `channels` (e.g. `trades`,
+/// `book`, `auction`) of `symbols`.
+pub fn subscribe_frame(symbols: &[&str], channels: &[&str]) -> String {
+    emit_frame("subscribe", symbols, channels)
+}
+
+/// Returns the socket.io frame unsubscribing from `channels` of `symbols`.
+pub fn unsubscribe_frame(symbols: &[&str], channels: &[&str]) -> String {
+    emit_frame("unsubscribe", symbols, channels)
+}
+
+fn emit_frame(event: &str, symbols: &[&str], channels: &[&str]) -> String {
+    let args = serde_json::json!({ "symbols": symbols, "channels": channels }).to_string();
+    format!("42{},{}", DEEP_NAMESPACE, serde_json::json!([event, args]))
+}
+
+/// Sends socket.io frames over a WebSocket managed by the caller.
+pub trait FrameSink {
+    fn send_frame(&mut self, frame: &str) -> Result<()>;
+}
+
+impl<F> FrameSink for F
+where
+    F: FnMut(&str) -> Result<()>,
+{
+    fn send_frame(&mut self, frame: &str) -> Result<()> {
+        self(frame)
+    }
+}
+
+/// Subscription to DEEP channels of some symbols, which unsubscribes when
+/// closed or dropped so reconnect loops don't leave orphaned subscriptions
+/// behind on IEX's side.
+pub struct DeepSubscription<S: FrameSink> {
+    sink: Option<S>,
+    symbols: Vec<String>,
+    channels: Vec<String>,
+}
+
+impl<S: FrameSink> DeepSubscription<S> {
+    /// Sends the `subscribe` frame for `channels` of `symbols` through
+    /// `sink`.
+    pub fn subscribe(mut sink: S, symbols: &[&str], channels: &[&str]) -> Result<Self> {
+        sink.send_frame(&subscribe_frame(symbols, channels))?;
+        Ok(DeepSubscription {
+            sink: Some(sink),
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            channels: channels.iter().map(|c| c.to_string()).collect(),
+        })
+    }
+
+    /// Sends the `unsubscribe` frame and returns the sink, e.g. to close the
+    /// connection. Unlike dropping the subscription, reports send failures.
+    pub fn close(mut self) -> Result<S> {
+        let mut sink = self.sink.take().unwrap();
+        sink.send_frame(&self.unsubscribe_frame())?;
+        Ok(sink)
+    }
+
+    /// Returns the sink, e.g. to read from the connection it writes to.
+    pub fn sink_mut(&mut self) -> &mut S {
+        self.sink.as_mut().unwrap()
+    }
+
+    fn unsubscribe_frame(&self) -> String {
+        let symbols: Vec<&str> = self.symbols.iter().map(|s| s.as_str()).collect();
+        let channels: Vec<&str> = self.channels.iter().map(|c| c.as_str()).collect();
+        unsubscribe_frame(&symbols, &channels)
+    }
+}
+
+impl<S: FrameSink> Drop for DeepSubscription<S> {
+    fn drop(&mut self) {
+        let frame = self.unsubscribe_frame();
+        if let Some(sink) = self.sink.as_mut() {
+            let _ = sink.send_frame(&frame);
+        }
+    }
+}
+
+/// Strips the engine.io/socket.io prefix (`42/1.0/deep,`) off an event frame.
+fn event_payload(frame: &str) -> Option<&str> {
+    let rest = frame.strip_prefix("42")?;
+    let rest = match rest.strip_prefix(DEEP_NAMESPACE) {
+        Some(rest) => rest.strip_prefix(',')?,
+        None => rest,
+    };
+    Some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HaltReason;
+
+    #[test]
+    fn subscribe_frame_format() {
+        assert_eq!(
+            subscribe_frame(&["snap"], &["trades"]),
+            r#"42/1.0/deep,["subscribe","{\"channels\":[\"trades\"],\"symbols\":[\"snap\"]}"]"#
+        );
+        assert!(
+            unsubscribe_frame(&["snap"], &["book"]).starts_with(r#"42/1.0/deep,["unsubscribe","#)
+        );
+    }
+
+    #[test]
+    fn unsubscribe_on_drop() {
+        let mut frames = Vec::new();
+        {
+            let mut sink = |frame: &str| {
+                frames.push(frame.to_string());
+                Ok(())
+            };
+            let _sub =
+                DeepSubscription::subscribe(&mut sink, &["snap", "fb"], &["trades"]).unwrap();
+        }
+        assert_eq!(
+            frames,
+            vec![
+                subscribe_frame(&["snap", "fb"], &["trades"]),
+                unsubscribe_frame(&["snap", "fb"], &["trades"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsubscribe_on_close() {
+        let mut frames = Vec::new();
+        let sink = |frame: &str| {
+            frames.push(frame.to_string());
+            Ok(())
+        };
+        let sub = DeepSubscription::subscribe(sink, &["snap"], &["book"]).unwrap();
+        let _ = sub.close().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1], unsubscribe_frame(&["snap"], &["book"]));
+    }
+
+    #[test]
+    fn decode_trade_frame() {
+        let body = serde_json::json!({
+            "symbol": "SNAP",
+            "messageType": "trades",
+            "data": {
+                "price": 156.1, "size": 100, "tradeId": 517341294,
+                "isISO": false, "isOddLot": false, "isOutsideRegularHours": false,
+                "isSinglePriceCross": false, "isTradeThroughExempt": false,
+                "timestamp": 1494619192003u64
+            }
+        });
+        let frame = format!(
+            "42/1.0/deep,{}",
+            serde_json::json!(["message", body.to_string()])
+        );
+        match DeepMessage::from_frame(&frame).unwrap() {
+            Some(DeepMessage::Trade { symbol, trade }) => {
+                assert_eq!(symbol, "SNAP");
+                assert_eq!(trade.size, 100.0);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_book_and_unknown() {
+        let book = r#"{"symbol":"SNAP","messageType":"book","data":{"bids":[{"price":1.5,"size":10,"timestamp":1}],"asks":[]}}"#;
+        match DeepMessage::from_json(book).unwrap() {
+            DeepMessage::Book { book, .. } => assert_eq!(book.bids.len(), 1),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let ssr = r#"{"symbol":"SNAP","messageType":"ssr","data":{"isSSR":false}}"#;
+        match DeepMessage::from_json(ssr).unwrap() {
+            DeepMessage::Other { message_type, .. } => assert_eq!(message_type, "ssr"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_trading_status() {
+        let halt = r#"{"symbol":"SNAP","messageType":"tradingstatus",
+            "data":{"status":"H","reason":"T1","timestamp":1494595567138}}"#;
+        match DeepMessage::from_json(halt).unwrap() {
+            DeepMessage::TradingStatus { symbol, status } => {
+                assert_eq!(symbol, "SNAP");
+                assert!(status.is_halted());
+                assert_eq!(status.reason, HaltReason::News);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn non_event_frames_are_skipped() {
+        assert!(DeepMessage::from_frame("3").unwrap().is_none());
+        assert!(DeepMessage::from_frame("40/1.0/deep,").unwrap().is_none());
+    }
+
+    #[test]
+    fn book_builder() {
+        let level = |price, size| BookLevel {
+            price,
+            size,
+            timestamp: 0.0,
+        };
+        let set = |side, price, size| BookUpdate::Set {
+            side,
+            level: level(price, size),
+        };
+        let mut book = BookBuilder::from_snapshot(DeepBook {
+            bids: vec![level(10.0, 100.0)],
+            asks: vec![level(10.2, 100.0)],
+        });
+        book.apply(set(BookSide::Bid, 10.1, 200.0));
+        book.apply(set(BookSide::Bid, 9.9, 300.0));
+        book.apply(set(BookSide::Ask, 10.3, 100.0));
+        book.apply(set(BookSide::Ask, 10.15, 50.0));
+        book.apply(set(BookSide::Bid, 10.0, 150.0));
+        book.apply(set(BookSide::Ask, 10.2, 0.0));
+        book.apply(BookUpdate::Delete {
+            side: BookSide::Bid,
+            price: 9.9,
+        });
+        book.apply(BookUpdate::Delete {
+            side: BookSide::Ask,
+            price: 11.0,
+        });
+
+        let snapshot = book.snapshot();
+        assert_eq!(snapshot.bids, vec![level(10.1, 200.0), level(10.0, 150.0)]);
+        assert_eq!(snapshot.asks, vec![level(10.15, 50.0), level(10.3, 100.0)]);
+    }
+}
@@ -0,0 +1,420 @@
+/*! DEEP WebSocket stream
+
+`DeepStream` connects to the DEEP socket.io channel over a WebSocket,
+subscribes to channels of some symbols and yields the decoded `DeepMessage`s:
+
+```no_run
+use iex::prelude::*;
+
+let stream = DeepStream::connect(&["snap", "fb"], &["trades", "book"]).unwrap();
+for message in stream {
+    println!("{:?}", message.unwrap());
+}
+```
+
+When the connection drops, the stream connects and subscribes again, waiting
+before every attempt as configured by its `RetryConfig`. Dropping the stream
+unsubscribes from its channels and closes the connection, so reconnect loops
+don't leave orphaned subscriptions behind on IEX's side.
+ */
+
+use super::{
+    DeepMessage, DeepSubscription, FrameSink, IexError, Result, RetryConfig, DEEP_NAMESPACE,
+};
+use std::io;
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+/// socket.io endpoint of the IEX WebSocket API.
+pub const DEEP_SOCKET_URL: &str =
+    "wss://ws-api.iextrading.com/socket.io/?EIO=3&transport=websocket";
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// WebSocket of a `DeepStream`, closed when dropped.
+struct Connection(Socket);
+
+impl FrameSink for Connection {
+    fn send_frame(&mut self, frame: &str) -> Result<()> {
+        send_text(&mut self.0, frame.to_string())
+    }
+}
+
+impl Connection {
+    /// Sends the WebSocket close frame.
+    fn close(&mut self) -> Result<()> {
+        self.0.close(None).map_err(ws_error)?;
+        self.0.flush().map_err(ws_error)
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// engine.io `open` packet sent by the server when the connection opens.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Handshake {
+    /// Milliseconds between the pings the client must send.
+    ping_interval: u64,
+}
+
+/// Stream of the messages of the DEEP channel, see the module documentation.
+pub struct DeepStream {
+    url: String,
+    symbols: Vec<String>,
+    channels: Vec<String>,
+    retry: RetryConfig,
+    subscription: Option<DeepSubscription<Connection>>,
+    /// Reconnections since the last message received.
+    reconnects: u32,
+}
+
+impl DeepStream {
+    /// Connects to the DEEP channel of IEX and subscribes to `channels`
+    /// (e.g. `trades`, `book`, `auction`) of `symbols`.
+    pub fn connect(symbols: &[&str], channels: &[&str]) -> Result<Self> {
+        DeepStream::connect_to(DEEP_SOCKET_URL, symbols, channels, RetryConfig::default())
+    }
+
+    /// Same as `connect`, connecting to the socket.io endpoint `url` and
+    /// reconnecting as configured by `retry`. Reconnecting fails once
+    /// `retry.max_retries` reconnections in a row brought no message.
+    pub fn connect_to(
+        url: &str,
+        symbols: &[&str],
+        channels: &[&str],
+        retry: RetryConfig,
+    ) -> Result<Self> {
+        let mut stream = DeepStream {
+            url: url.to_string(),
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            channels: channels.iter().map(|c| c.to_string()).collect(),
+            retry,
+            subscription: None,
+            reconnects: 0,
+        };
+        stream.subscription = Some(stream.open()?);
+        Ok(stream)
+    }
+
+    /// Unsubscribes and closes the connection. Unlike dropping the stream,
+    /// reports failures.
+    pub fn close(mut self) -> Result<()> {
+        match self.subscription.take() {
+            Some(subscription) => subscription.close()?.close(),
+            None => Ok(()),
+        }
+    }
+
+    /// Waits for the next message, reconnecting if the connection drops.
+    pub fn next_message(&mut self) -> Result<DeepMessage> {
+        loop {
+            let frame = match self.subscription.as_mut() {
+                Some(subscription) => read_frame(&mut subscription.sink_mut().0),
+                None => {
+                    self.reconnect()?;
+                    continue;
+                }
+            };
+            match frame {
+                Ok(Some(frame)) => {
+                    if let Some(message) = DeepMessage::from_frame(&frame)? {
+                        self.reconnects = 0;
+                        return Ok(message);
+                    }
+                }
+                Ok(None) => {}
+                Err(_) => self.subscription = None,
+            }
+        }
+    }
+
+    /// Opens a connection, joins the DEEP namespace and subscribes.
+    fn open(&self) -> Result<DeepSubscription<Connection>> {
+        let (mut socket, _) = tungstenite::connect(self.url.as_str()).map_err(ws_error)?;
+        let open = match socket.read().map_err(ws_error)? {
+            Message::Text(text) => text,
+            message => format!("{:?}", message),
+        };
+        let handshake: Handshake = open
+            .strip_prefix('0')
+            .and_then(|json| serde_json::from_str(json).ok())
+            .ok_or_else(|| IexError::Json {
+                message: format!("unexpected engine.io handshake: {}", open),
+            })?;
+        // The read timeout wakes `read_frame` up in time to ping the server.
+        set_read_timeout(&socket, Duration::from_millis(handshake.ping_interval))
+            .map_err(|err| ws_error(err.into()))?;
+        send_text(&mut socket, format!("40{},", DEEP_NAMESPACE))?;
+        let symbols: Vec<&str> = self.symbols.iter().map(|s| s.as_str()).collect();
+        let channels: Vec<&str> = self.channels.iter().map(|c| c.as_str()).collect();
+        DeepSubscription::subscribe(Connection(socket), &symbols, &channels)
+    }
+
+    /// Connects again after the connection dropped, backing off as
+    /// configured by `retry`.
+    fn reconnect(&mut self) -> Result<()> {
+        let mut previous = None;
+        loop {
+            let delay = self.retry.delay(self.reconnects, previous);
+            thread::sleep(delay);
+            previous = Some(delay);
+            self.reconnects += 1;
+            match self.open() {
+                Ok(subscription) => {
+                    self.subscription = Some(subscription);
+                    return Ok(());
+                }
+                Err(err) if self.reconnects > self.retry.max_retries => return Err(err),
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+impl Iterator for DeepStream {
+    type Item = Result<DeepMessage>;
+
+    /// Returns the next message. Never returns `None`; a failed
+    /// reconnection is returned as an error.
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_message())
+    }
+}
+
+/// Reads the next socket.io frame. Returns `None` for frames handled here,
+/// e.g. engine.io pings, and when no frame arrived within the ping interval,
+/// after pinging the server. Reports the end of the connection as an error.
+fn read_frame(socket: &mut Socket) -> Result<Option<String>> {
+    let text = match socket.read() {
+        Ok(Message::Text(text)) => text,
+        Ok(Message::Close(_)) => return Err(disconnected()),
+        Ok(_) => return Ok(None),
+        Err(tungstenite::Error::Io(ref err))
+            if err.kind() == io::ErrorKind::WouldBlock || err.kind() == io::ErrorKind::TimedOut =>
+        {
+            send_text(socket, "2".to_string())?;
+            return Ok(None);
+        }
+        Err(err) => return Err(ws_error(err)),
+    };
+    match text.as_str() {
+        "1" => Err(disconnected()),
+        "2" => send_text(socket, "3".to_string()).map(|_| None),
+        "3" => Ok(None),
+        frame if frame.starts_with(&format!("41{}", DEEP_NAMESPACE)) => Err(disconnected()),
+        _ => Ok(Some(text)),
+    }
+}
+
+fn send_text(socket: &mut Socket, text: String) -> Result<()> {
+    socket.send(Message::Text(text)).map_err(ws_error)?;
+    Ok(())
+}
+
+fn set_read_timeout(socket: &Socket, timeout: Duration) -> io::Result<()> {
+    match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(timeout)),
+        MaybeTlsStream::NativeTls(stream) => stream.get_ref().set_read_timeout(Some(timeout)),
+        _ => Ok(()),
+    }
+}
+
+fn disconnected() -> failure::Error {
+    IexError::Unreachable {
+        message: "DEEP connection closed".to_string(),
+    }
+    .into()
+}
+
+fn ws_error(err: tungstenite::Error) -> failure::Error {
+    IexError::Unreachable {
+        message: err.to_string(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{subscribe_frame, unsubscribe_frame, JitterStrategy};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    const HANDSHAKE: &str =
+        r#"0{"sid":"abc","upgrades":[],"pingInterval":25000,"pingTimeout":60000}"#;
+
+    fn message_frame(message_type: &str, data: serde_json::Value) -> String {
+        let body = serde_json::json!({"symbol": "SNAP", "messageType": message_type, "data": data});
+        format!(
+            "42{},{}",
+            DEEP_NAMESPACE,
+            serde_json::json!(["message", body.to_string()])
+        )
+    }
+
+    fn trade_frame() -> String {
+        message_frame(
+            "trades",
+            serde_json::json!({
+                "price": 156.1, "size": 100, "tradeId": 517341294,
+                "isISO": false, "isOddLot": false, "isOutsideRegularHours": false,
+                "isSinglePriceCross": false, "isTradeThroughExempt": false,
+                "timestamp": 1494619192003u64
+            }),
+        )
+    }
+
+    fn book_frame() -> String {
+        message_frame(
+            "book",
+            serde_json::json!({"bids": [{"price": 1.5, "size": 10, "timestamp": 1}], "asks": []}),
+        )
+    }
+
+    /// Returns a listener for the mock socket.io server and its URL.
+    fn listen() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "ws://{}/socket.io/?EIO=3&transport=websocket",
+            listener.local_addr().unwrap()
+        );
+        (listener, url)
+    }
+
+    /// Accepts a connection as a socket.io server: sends the handshake and
+    /// reads the namespace connect and subscribe frames, which are returned
+    /// along with the socket.
+    fn accept(listener: &TcpListener) -> (WebSocket<TcpStream>, Vec<String>) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept(stream).unwrap();
+        socket.send(Message::Text(HANDSHAKE.to_string())).unwrap();
+        socket.send(Message::Text("40".to_string())).unwrap();
+        let received = (0..2)
+            .map(|_| socket.read().unwrap().into_text().unwrap())
+            .collect();
+        (socket, received)
+    }
+
+    /// Mock socket.io server accepting one connection per item of
+    /// `connections`, sending the frames of the item and dropping the
+    /// connection. Returns the URL of the server and a handle returning the
+    /// frames received on every connection.
+    fn serve(connections: Vec<Vec<String>>) -> (String, JoinHandle<Vec<Vec<String>>>) {
+        let (listener, url) = listen();
+        let handle = thread::spawn(move || {
+            connections
+                .into_iter()
+                .map(|frames| {
+                    let (mut socket, received) = accept(&listener);
+                    for frame in frames {
+                        socket.send(Message::Text(frame)).unwrap();
+                    }
+                    received
+                })
+                .collect()
+        });
+        (url, handle)
+    }
+
+    /// Mock socket.io server sending a trade on a single connection, then
+    /// reading until the client closes it. The handle returns the frames
+    /// received after the subscription, and whether a close frame was.
+    fn serve_until_close() -> (String, JoinHandle<(Vec<String>, bool)>) {
+        let (listener, url) = listen();
+        let handle = thread::spawn(move || {
+            let (mut socket, _) = accept(&listener);
+            socket.send(Message::Text(trade_frame())).unwrap();
+            let mut received = Vec::new();
+            loop {
+                match socket.read() {
+                    Ok(Message::Text(text)) => received.push(text),
+                    Ok(Message::Close(_)) => return (received, true),
+                    Ok(_) => {}
+                    Err(_) => return (received, false),
+                }
+            }
+        });
+        (url, handle)
+    }
+
+    fn retry() -> RetryConfig {
+        RetryConfig {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            jitter: JitterStrategy::None,
+        }
+    }
+
+    #[test]
+    fn stream_reconnects_and_subscribes_again() {
+        let (url, server) = serve(vec![vec![trade_frame()], vec![book_frame()]]);
+        let mut stream =
+            DeepStream::connect_to(&url, &["snap"], &["trades", "book"], retry()).unwrap();
+
+        match stream.next_message().unwrap() {
+            DeepMessage::Trade { symbol, trade } => {
+                assert_eq!(symbol, "SNAP");
+                assert_eq!(trade.size, 100.0);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        match stream.next_message().unwrap() {
+            DeepMessage::Book { book, .. } => assert_eq!(book.bids.len(), 1),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let subscription = vec![
+            "40/1.0/deep,".to_string(),
+            subscribe_frame(&["snap"], &["trades", "book"]),
+        ];
+        assert_eq!(
+            server.join().unwrap(),
+            vec![subscription.clone(), subscription]
+        );
+    }
+
+    #[test]
+    fn stream_gives_up_after_max_retries() {
+        let (url, server) = serve(vec![vec![]]);
+        let mut stream = DeepStream::connect_to(&url, &["snap"], &["trades"], retry()).unwrap();
+        server.join().unwrap();
+
+        match stream.next_message().unwrap_err().downcast::<IexError>() {
+            Ok(IexError::Unreachable { .. }) => {}
+            err => panic!("expected IexError::Unreachable, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn drop_unsubscribes_and_closes() {
+        let (url, server) = serve_until_close();
+        let mut stream = DeepStream::connect_to(&url, &["snap"], &["trades"], retry()).unwrap();
+        stream.next_message().unwrap();
+        drop(stream);
+
+        let (received, closed) = server.join().unwrap();
+        assert_eq!(received, vec![unsubscribe_frame(&["snap"], &["trades"])]);
+        assert!(closed);
+    }
+
+    #[test]
+    fn close_unsubscribes_and_closes() {
+        let (url, server) = serve_until_close();
+        let mut stream = DeepStream::connect_to(&url, &["snap"], &["trades"], retry()).unwrap();
+        stream.next_message().unwrap();
+        stream.close().unwrap();
+
+        let (received, closed) = server.join().unwrap();
+        assert_eq!(received, vec![unsubscribe_frame(&["snap"], &["trades"])]);
+        assert!(closed);
+    }
+}
@@ -0,0 +1,269 @@
+/*! Sample IEX payloads shared by the unit tests.
+ */
+
+use super::Money;
+
+/// Parses a money amount, e.g. to compare with a deserialized price.
+pub fn money(s: &str) -> Money {
+    s.parse().unwrap()
+}
+
+/// `/stock/aapl/quote`
+pub const QUOTE: &str = r#"{
+    "symbol": "AAPL",
+    "companyName": "Apple Inc.",
+    "primaryExchange": "Nasdaq Global Select",
+    "sector": "Technology",
+    "calculationPrice": "tops",
+    "open": 221.5,
+    "openTime": 1540388000000,
+    "close": 221.5,
+    "closeTime": 1540411200000,
+    "high": 223.0,
+    "low": 220.9,
+    "latestPrice": 222.73,
+    "latestSource": "IEX real time price",
+    "latestTime": "11:25:12 AM",
+    "latestUpdate": 1540394712345,
+    "latestVolume": 32112345,
+    "iexRealtimePrice": 222.73,
+    "iexRealtimeSize": 100,
+    "iexLastUpdated": 1540394712345,
+    "delayedPrice": 222.6,
+    "delayedPriceTime": 1540393812345,
+    "previousClose": 221.5,
+    "change": 1.23,
+    "changePercent": 0.00555,
+    "iexMarketPercent": 0.0312,
+    "iexVolume": 1001900,
+    "avgTotalVolume": 29876543,
+    "iexBidPrice": 222.7,
+    "iexBidSize": 100,
+    "iexAskPrice": 222.76,
+    "iexAskSize": 200,
+    "marketCap": 1076000000000,
+    "peRatio": 20.21,
+    "week52High": 233.47,
+    "week52Low": 150.24,
+    "ytdChange": 0.3112
+}"#;
+
+/// `/stock/aapl/stats`
+pub const STATS: &str = r#"{
+    "companyName": "Apple Inc.",
+    "marketcap": 1076000000000,
+    "beta": 1.15,
+    "week52high": 233.47,
+    "week52low": 150.24,
+    "week52change": 0.2571,
+    "shortInterest": 40174502,
+    "shortDate": "2018-10-15",
+    "dividendRate": 2.92,
+    "dividendYield": 1.31,
+    "exDividendDate": "2018-08-10",
+    "latestEPS": 11.91,
+    "latestEPSDate": "2018-09-29",
+    "sharesOutstanding": 4829926000,
+    "float": 4825025726,
+    "returnOnEquity": 49.36,
+    "consensusEPS": 2.78,
+    "numberOfEstimates": 14,
+    "EPSSurprisePercent": 3.6,
+    "EPSSurpriseDollar": 0.1,
+    "symbol": "AAPL",
+    "EBITDA": 81801000000,
+    "revenue": 265595000000,
+    "grossProfit": 101839000000,
+    "cash": 66301000000,
+    "debt": 114483000000,
+    "ttmEPS": 11.91,
+    "returnOnCapital": null,
+    "revenuePerShare": 53,
+    "revenuePerEmployee": 2013606,
+    "peRatioHigh": 19.6,
+    "peRatioLow": 12.6,
+    "returnOnAssets": 16.07,
+    "profitMargin": 22.41,
+    "priceToSales": 4.05,
+    "priceToBook": 10.31,
+    "day200MovingAvg": 196.53,
+    "day50MovingAvg": 221.88,
+    "institutionPercent": 61.1,
+    "insiderPercent": 0.1,
+    "shortRatio": 1.24,
+    "year5ChangePercent": 2.13,
+    "year2ChangePercent": 0.88,
+    "year1ChangePercent": 0.26,
+    "ytdChangePercent": 0.31,
+    "month6ChangePercent": 0.24,
+    "month3ChangePercent": 0.17,
+    "month1ChangePercent": -0.01,
+    "day5ChangePercent": 0.02,
+    "day30ChangePercent": -0.01
+}"#;
+
+/// `/stock/aapl/company`
+pub const COMPANY: &str = r#"{
+    "symbol": "AAPL",
+    "companyName": "Apple Inc.",
+    "exchange": "Nasdaq Global Select",
+    "industry": "Computer Hardware",
+    "website": "http://www.apple.com",
+    "description": "Apple Inc is designs, manufactures and markets mobile communication and media devices.",
+    "CEO": "Timothy D. Cook",
+    "issueType": "cs",
+    "sector": "Technology",
+    "tags": ["Technology", "Consumer Electronics", "Computer Hardware"]
+}"#;
+
+/// `/ref-data/daily-list/corporate-actions/sample`, single record
+pub const CORPORATE_ACTION: &str = r#"{
+    "RecordID": " CA20171108153808144",
+    "DailyListTimestamp": "2017-11-08T17:00:00",
+    "EffectiveDate": "2017-11-10",
+    "IssueEvent": "AA",
+    "CurrentSymbolinINETSymbology": "ZEXIT-",
+    "CurrentSymbolinCQSSymbology": "ZEXITp",
+    "CurrentSymbolinCMSSymbology": "ZEXIT PR",
+    "NewSymbolinINETSymbology": "",
+    "NewSymbolinCQSSymbology": "",
+    "NewSymbolinCMSSymbology": "",
+    "CurrentSecurityName": "ZEXIT Preffered Stock",
+    "NewSecurityName": "",
+    "CurrentCompanyName": "ZEXIT Test Company",
+    "NewCompanyName": "",
+    "CurrentListingCenter": "",
+    "NewListingCenter": "V",
+    "DelistingReason": "",
+    "CurrentRoundLotSize": "100",
+    "NewRoundLotSize": "",
+    "CurrentLULDTierIndicator": "0",
+    "NewLULDTierIndicator": "",
+    "ExpirationDate": "0",
+    "SeparationDate": "0",
+    "SettlementDate": "0",
+    "MaturityDate": "0",
+    "RedemptionDate": "0",
+    "CurrentFinancialStatus": "0",
+    "NewFinancialStatus": "",
+    "WhenIssuedFlag": "N",
+    "WhenDistributedFlag": "N",
+    "IPOFlag": "N",
+    "NotesforEachEntry": "New preferred ZIEXT security",
+    "RecordUpdateTime": "2017-11-08T16:34:43"
+}"#;
+
+/// `/ref-data/daily-list/dividends/sample`, single record
+pub const DAILY_DIVIDEND: &str = r#"{
+    "RecordID": " DV20171108154436478",
+    "DailyListTimestamp": "2017-11-08T17:00:00",
+    "EventType": "CHANGE",
+    "SymbolinINETSymbology": "ZEXIT",
+    "SymbolinCQSSymbology": "ZEXIT",
+    "SymbolinCMSSymbology": "ZEXIT",
+    "SecurityName": "ZEXIT Common Stock",
+    "CompanyName": "ZEXIT Test Company",
+    "DeclarationDate": "2017-11-01",
+    "AmountDescription": "fnl",
+    "PaymentFrequency": "Q",
+    "ExDate": "2017-11-09",
+    "RecordDate": "2017-11-13",
+    "PaymentDate": "2017-11-17",
+    "DividendTypeID": "XS",
+    "StockAdjustmentFactor": "1.1",
+    "StockAmount": ".1",
+    "CashAmount": "0.09",
+    "PostSplitShares": "0",
+    "PreSplitShares": "0",
+    "QualifiedDividend": "Y",
+    "ExercisePriceAmount": "0.0",
+    "ElectionorExpirationDate": "",
+    "GrossAmount": "0.09",
+    "NetAmount": "",
+    "BasisNotes": "",
+    "NotesforEachEntry": "ZEXIT is paying a 10% stock dividend",
+    "RecordUpdateTime": "2017-11-08T15:44:36"
+}"#;
+
+/// `/ref-data/daily-list/next-day-ex-date/sample`, single record
+pub const NEXT_DAY_EX_DATE: &str = r#"{
+    "RecordID": " NX20171108154436477",
+    "DailyListTimestamp": "2017-11-08T17:00:00",
+    "ExDate": "2017-11-09",
+    "SymbolinINETSymbology": "ZEXIT",
+    "SymbolinCQSSymbology": "ZEXIT",
+    "SymbolinCMSSymbology": "ZEXIT",
+    "SecurityName": "ZEXIT Common Stock",
+    "CompanyName": "ZEXIT Test Company",
+    "DividendTypeID": "XS",
+    "AmountDescription": "fnl",
+    "PaymentFrequency": "Q",
+    "StockAdjustmentFactor": "1.1",
+    "StockAmount": ".1",
+    "CashAmount": "0.09",
+    "PostSplitShares": "0",
+    "PreSplitShares": "0",
+    "QualifiedDividend": "Y",
+    "ExercisePriceAmount": "0.0",
+    "ElectionorExpirationDate": "",
+    "GrossAmount": "0.09",
+    "NetAmount": "",
+    "BasisNotes": "",
+    "NotesforEachEntry": "ZEXIT is paying a 10% stock dividend",
+    "RecordUpdateTime": "2017-11-08T15:44:36"
+}"#;
+
+/// `/ref-data/daily-list/symbol-directory/sample`, single record
+pub const SYMBOL_DIRECTORY: &str = r#"{
+    "RecordID": " SD20171020161150890",
+    "DailyListTimestamp": "2017-12-18T09:00:00",
+    "SymbolinINETSymbology": "ZEXIT-",
+    "SymbolinCQSSymbology": "ZEXITp",
+    "SymbolinCMSSymbology": "ZEXIT PR",
+    "SecurityName": "ZEXIT Preffered Stock",
+    "CompanyName": "ZEXIT Test Company",
+    "TestIssue": "Y",
+    "IssueDescription": "Test Preffered Stock",
+    "IssueType": "P",
+    "IssueSubType": "Z",
+    "SIC": "5678",
+    "TransferAgent": "American Stock Transfer",
+    "FinancialStatus": "0",
+    "RoundLotSize": "100",
+    "PreviousOfficialClosingPrice": "",
+    "AdjustedPreviousOfficialClosingPrice": "",
+    "WhenIssuedFlag": "N",
+    "WhenDistributedFlag": "N",
+    "IPOFlag": "N",
+    "FirstDateListed": "2017-09-15",
+    "LULDTierIndicator": "1",
+    "CountryofIncorporation": "USA",
+    "LeveragedETPFlag": "N",
+    "LeveragedETPRatio": "",
+    "InverseETPFlag": "N",
+    "RecordUpdateTime": "2017-10-20T16:11:50"
+}"#;
+
+/// `/ref-data/daily-list/corporate-actions/sample?format=psv`, without the
+/// CUSIP column.
+pub const CORPORATE_ACTIONS_PSV: &str = r#"RecordID|DailyListTimestamp|EffectiveDate|IssueEvent|CurrentSymbolinINETSymbology|CurrentSymbolinCQSSymbology|CurrentSymbolinCMSSymbology|NewSymbolinINETSymbology|NewSymbolinCQSSymbology|NewSymbolinCMSSymbology|CurrentSecurityName|NewSecurityName|CurrentCompanyName|NewCompanyName|CurrentListingCenter|NewListingCenter|DelistingReason|CurrentRoundLotSize|NewRoundLotSize|CurrentLULDTierIndicator|NewLULDTierIndicator|ExpirationDate|SeparationDate|SettlementDate|MaturityDate|RedemptionDate|CurrentFinancialStatus|NewFinancialStatus|WhenIssuedFlag|WhenDistributedFlag|IPOFlag|NotesforEachEntry|RecordUpdateTime
+ CA20171108153808144|2017-11-08T17:00:00|2017-11-10|AA|ZEXIT-|ZEXITp|ZEXIT PR||||ZEXIT Preffered Stock||ZEXIT Test Company|||V||100||0||0|0|0|0|0|0||N|N|N|New preferred ZIEXT security|2017-11-08T16:34:43
+ CA20171109101500211|2017-11-09T17:00:00|2017-11-13|AA|ZIEXT|ZIEXT|ZIEXT||||ZIEXT Common Stock||ZIEXT Test Company|||V|||100||1|0|0|0|0|0|0||N|N|Y|"IPO, with a | in the notes"|2017-11-09T10:15:00
+"#;
+
+/// `/deep/auction?symbols=ziext`, auction information of `ZIEXT`
+pub const AUCTION: &str = r#"{
+    "auctionType": "Close",
+    "pairedShares": 2000,
+    "imbalanceShares": 0,
+    "imbalanceSide": "None",
+    "referencePrice": 10.05,
+    "indicativePrice": 10.07,
+    "auctionBookPrice": 10.07,
+    "collarReferencePrice": 10.05,
+    "lowerCollarPrice": 0.5,
+    "upperCollarPrice": 1.5,
+    "extensionNumber": 0,
+    "startTime": 1540324800000,
+    "timestamp": 1540324799126
+}"#;
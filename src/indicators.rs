@@ -0,0 +1,535 @@
+/*! Technical indicators
+
+Simple indicators computed over the close prices of a chart:
+
+```no_run
+# #[cfg(feature = "blocking")]
+# fn main() {
+use iex::prelude::*;
+
+let client = Client::new();
+let chart = client.chart("aapl", Duration::OneYear, None).unwrap();
+let sma = chart.sma(20);
+let ema = chart.ema(20);
+let returns = chart.daily_returns();
+let weekly = chart.resample(ResamplePeriod::Weekly);
+let columns = chart.into_columns();
+# }
+# #[cfg(not(feature = "blocking"))]
+# fn main() {}
+```
+
+and aggregates over trades, e.g. the `trades` of a `StockBook`, as well as
+the venues where a symbol gets the best execution:
+
+```no_run
+# #[cfg(feature = "blocking")]
+# fn main() {
+use iex::prelude::*;
+
+let client = Client::new();
+let spreads = client.effective_spread("aapl").unwrap();
+let best = spreads.best_venue().map(|s| s.venue_name.as_str());
+# }
+# #[cfg(not(feature = "blocking"))]
+# fn main() {}
+```
+ */
+
+use super::{ChartDataPoint, EffectiveSpread, Trade};
+use chrono::{Datelike, NaiveDate};
+use std::cmp::Ordering;
+
+/// Calendar period `ChartSeries::resample` aggregates daily points into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResamplePeriod {
+    /// ISO week, Monday to Sunday.
+    Weekly,
+    Monthly,
+}
+
+impl ResamplePeriod {
+    fn key(self, date: NaiveDate) -> (i32, u32) {
+        match self {
+            ResamplePeriod::Weekly => {
+                let week = date.iso_week();
+                (week.year(), week.week())
+            }
+            ResamplePeriod::Monthly => (date.year(), date.month()),
+        }
+    }
+}
+
+/// Indicators over a chart, oldest point first.
+pub trait ChartSeries {
+    /// Simple moving average of the closes over `window` points.
+    ///
+    /// The first `window - 1` values are `None`, as are all values if
+    /// `window` is zero.
+    fn sma(&self, window: usize) -> Vec<Option<f64>>;
+
+    /// Exponential moving average of the closes over `period` points, with a
+    /// smoothing factor of `2 / (period + 1)`.
+    ///
+    /// The average is seeded with the simple moving average of the first
+    /// `period` closes, so the first `period - 1` values are `None`, as are
+    /// all values if `period` is zero.
+    fn ema(&self, period: usize) -> Vec<Option<f64>>;
+
+    /// Aggregates daily points into one point per calendar `period`, grouped
+    /// by their `date`: first open, highest high, lowest low, last close and
+    /// summed volumes. The `vwap` is weighted by volume and `change` is
+    /// relative to the close before the period.
+    ///
+    /// Points whose `date` isn't a `YYYY-MM-DD` date are skipped.
+    fn resample(&self, period: ResamplePeriod) -> Vec<ChartDataPoint>;
+
+    /// Return of every point over the previous close, `close / previous - 1`,
+    /// starting with the second point since the first has no previous
+    /// close.
+    ///
+    /// The return is `None` where the previous close is zero, e.g. a missing
+    /// price, like `cumulative_return`.
+    fn daily_returns(&self) -> Vec<Option<f64>>;
+
+    /// Return of the last close over the first one, or `None` if there are
+    /// fewer than two points or the first close is zero.
+    fn cumulative_return(&self) -> Option<f64>;
+}
+
+impl ChartSeries for [ChartDataPoint] {
+    fn sma(&self, window: usize) -> Vec<Option<f64>> {
+        let mut averages = Vec::with_capacity(self.len());
+        let mut sum = 0.0;
+        for (i, point) in self.iter().enumerate() {
+            sum += point.close;
+            if i >= window {
+                sum -= self[i - window].close;
+            }
+            if window > 0 && i + 1 >= window {
+                averages.push(Some(sum / window as f64));
+            } else {
+                averages.push(None);
+            }
+        }
+        averages
+    }
+
+    fn ema(&self, period: usize) -> Vec<Option<f64>> {
+        let k = 2.0 / (period as f64 + 1.0);
+        let mut averages = Vec::with_capacity(self.len());
+        let mut ema: Option<f64> = None;
+        for (i, point) in self.iter().enumerate() {
+            ema = match ema {
+                Some(prev) => Some(prev + k * (point.close - prev)),
+                None if period > 0 && i + 1 == period => {
+                    let seed: f64 = self[..period].iter().map(|p| p.close).sum();
+                    Some(seed / period as f64)
+                }
+                None => None,
+            };
+            averages.push(ema);
+        }
+        averages
+    }
+
+    fn resample(&self, period: ResamplePeriod) -> Vec<ChartDataPoint> {
+        let mut bars: Vec<ChartDataPoint> = Vec::new();
+        let mut current = None;
+        let mut prev_close = 0.0;
+        let mut traded = 0.0;
+        for point in self {
+            let date = match NaiveDate::parse_from_str(&point.date, "%Y-%m-%d") {
+                Ok(date) => date,
+                Err(_) => continue,
+            };
+            let key = period.key(date);
+            match bars.last_mut() {
+                Some(bar) if current == Some(key) => {
+                    bar.high = bar.high.max(point.high);
+                    bar.low = bar.low.min(point.low);
+                    bar.close = point.close;
+                    bar.volume += point.volume;
+                    bar.unadjusted_volume += point.unadjusted_volume;
+                    bar.change_over_time = point.change_over_time;
+                    bar.adjusted_close = point.adjusted_close;
+                    traded += point.vwap * point.volume;
+                }
+                _ => {
+                    current = Some(key);
+                    prev_close = point.close - point.change;
+                    traded = point.vwap * point.volume;
+                    bars.push(ChartDataPoint {
+                        date: point.date.clone(),
+                        open: point.open,
+                        high: point.high,
+                        low: point.low,
+                        close: point.close,
+                        volume: point.volume,
+                        unadjusted_volume: point.unadjusted_volume,
+                        change: 0.0,
+                        change_percent: 0.0,
+                        vwap: point.vwap,
+                        label: point.label.clone(),
+                        change_over_time: point.change_over_time,
+                        adjusted_close: point.adjusted_close,
+                    });
+                }
+            }
+            let bar = bars.last_mut().unwrap();
+            bar.change = bar.close - prev_close;
+            if prev_close != 0.0 {
+                bar.change_percent = bar.change / prev_close * 100.0;
+            }
+            if bar.volume > 0.0 {
+                bar.vwap = traded / bar.volume;
+            }
+        }
+        bars
+    }
+
+    fn daily_returns(&self) -> Vec<Option<f64>> {
+        self.windows(2)
+            .map(|pair| match pair[0].close {
+                previous if previous != 0.0 => Some(pair[1].close / previous - 1.0),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn cumulative_return(&self) -> Option<f64> {
+        match (self.first(), self.last()) {
+            (Some(first), Some(last)) if self.len() > 1 && first.close != 0.0 => {
+                Some(last.close / first.close - 1.0)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Chart data in columnar form, one `Vec` per field with the values of the
+/// points at the same index, e.g. to build a data frame without per-row
+/// access.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChartColumns {
+    pub dates: Vec<String>,
+    pub opens: Vec<f64>,
+    pub highs: Vec<f64>,
+    pub lows: Vec<f64>,
+    pub closes: Vec<f64>,
+    pub volumes: Vec<f64>,
+}
+
+/// Conversion of row-oriented chart data into `ChartColumns`.
+pub trait IntoChartColumns {
+    fn into_columns(self) -> ChartColumns;
+}
+
+impl IntoChartColumns for Vec<ChartDataPoint> {
+    fn into_columns(self) -> ChartColumns {
+        let len = self.len();
+        let mut columns = ChartColumns {
+            dates: Vec::with_capacity(len),
+            opens: Vec::with_capacity(len),
+            highs: Vec::with_capacity(len),
+            lows: Vec::with_capacity(len),
+            closes: Vec::with_capacity(len),
+            volumes: Vec::with_capacity(len),
+        };
+        for point in self {
+            columns.dates.push(point.date);
+            columns.opens.push(point.open);
+            columns.highs.push(point.high);
+            columns.lows.push(point.low);
+            columns.closes.push(point.close);
+            columns.volumes.push(point.volume);
+        }
+        columns
+    }
+}
+
+/// Aggregates over trades.
+pub trait TradeSeries {
+    /// Volume-weighted average price, `None` if there are no trades or they
+    /// have no volume.
+    fn vwap(&self) -> Option<f64>;
+
+    /// Summed size of the trades.
+    fn total_volume(&self) -> f64;
+
+    fn trade_count(&self) -> usize;
+}
+
+impl TradeSeries for [Trade] {
+    fn vwap(&self) -> Option<f64> {
+        let volume = self.total_volume();
+        if volume > 0.0 {
+            let traded: f64 = self.iter().map(|t| t.price * t.size).sum();
+            Some(traded / volume)
+        } else {
+            None
+        }
+    }
+
+    fn total_volume(&self) -> f64 {
+        self.iter().map(|t| t.size).sum()
+    }
+
+    fn trade_count(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Smallest share of the summed volume of the venues a venue needs to be
+/// ranked by `VenueRanking`.
+const MIN_VENUE_VOLUME_SHARE: f64 = 0.01;
+
+/// Ranking of the venues of `Client::effective_spread` by execution
+/// quality.
+///
+/// Venues with a spread that isn't a number are skipped, as are venues
+/// with less than 1% of the summed volume of the venues, so that the spread
+/// of a venue which barely traded doesn't outrank the one of a venue which
+/// did. Ties between spreads go to the venue with the most volume.
+pub trait VenueRanking {
+    /// Returns the venue with the lowest effective spread, `None` if there
+    /// are none.
+    fn best_venue(&self) -> Option<&EffectiveSpread>;
+
+    /// Returns the venue with the highest effective spread, `None` if there
+    /// are none.
+    fn worst_venue(&self) -> Option<&EffectiveSpread>;
+}
+
+impl VenueRanking for [EffectiveSpread] {
+    fn best_venue(&self) -> Option<&EffectiveSpread> {
+        ranked_venues(self)
+            .min_by(|a, b| compare(a, b, |s| s.effective_spread).then(compare(b, a, |s| s.volume)))
+    }
+
+    fn worst_venue(&self) -> Option<&EffectiveSpread> {
+        ranked_venues(self)
+            .max_by(|a, b| compare(a, b, |s| s.effective_spread).then(compare(a, b, |s| s.volume)))
+    }
+}
+
+/// Venues of `spreads` which can be ranked, see `VenueRanking`.
+fn ranked_venues(spreads: &[EffectiveSpread]) -> impl Iterator<Item = &EffectiveSpread> {
+    let spreads = spreads.iter().filter(|s| !s.effective_spread.is_nan());
+    let volume: f64 = spreads.clone().map(|s| s.volume).sum();
+    spreads.filter(move |s| s.volume > 0.0 && s.volume >= volume * MIN_VENUE_VOLUME_SHARE)
+}
+
+/// Compares the `field` of two venues.
+fn compare<F>(a: &EffectiveSpread, b: &EffectiveSpread, field: F) -> Ordering
+where
+    F: Fn(&EffectiveSpread) -> f64,
+{
+    field(a).partial_cmp(&field(b)).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(closes: &[f64]) -> Vec<ChartDataPoint> {
+        closes
+            .iter()
+            .map(|&close| ChartDataPoint {
+                date: String::new(),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0.0,
+                unadjusted_volume: 0.0,
+                change: 0.0,
+                change_percent: 0.0,
+                vwap: close,
+                label: String::new(),
+                change_over_time: 0.0,
+                adjusted_close: None,
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: &[Option<f64>], expected: &[Option<f64>]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            match (a, e) {
+                (Some(a), Some(e)) => assert!((a - e).abs() < 1e-9, "{} != {}", a, e),
+                _ => assert_eq!(a, e),
+            }
+        }
+    }
+
+    #[test]
+    fn sma() {
+        let chart = chart(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_close(
+            &chart.sma(3),
+            &[None, None, Some(2.0), Some(3.0), Some(4.0)],
+        );
+        assert_close(
+            &chart.sma(1),
+            &[Some(1.0), Some(2.0), Some(3.0), Some(4.0), Some(5.0)],
+        );
+        assert_eq!(chart.sma(0), vec![None; 5]);
+        assert_eq!(chart.sma(6), vec![None; 5]);
+    }
+
+    #[test]
+    fn ema() {
+        let chart = chart(&[22.27, 22.19, 22.08, 22.17, 22.18]);
+        assert_close(
+            &chart.ema(3),
+            &[None, None, Some(22.18), Some(22.175), Some(22.1775)],
+        );
+        assert_eq!(chart.ema(0), vec![None; 5]);
+        assert_eq!(chart.ema(6), vec![None; 5]);
+    }
+
+    #[test]
+    fn returns() {
+        let chart = chart(&[100.0, 110.0, 99.0, 108.9]);
+        assert_close(&chart.daily_returns(), &[Some(0.1), Some(-0.1), Some(0.1)]);
+        assert_close(&[chart.cumulative_return()], &[Some(0.089)]);
+
+        assert!(chart[..1].daily_returns().is_empty());
+        assert_eq!(chart[..1].cumulative_return(), None);
+        assert_eq!(chart[..0].cumulative_return(), None);
+    }
+
+    #[test]
+    fn returns_over_a_zero_close() {
+        let chart = chart(&[100.0, 0.0, 50.0, 55.0]);
+        assert_close(&chart.daily_returns(), &[Some(-1.0), None, Some(0.1)]);
+        assert_eq!(chart[1..].cumulative_return(), None);
+    }
+
+    #[test]
+    fn resample_weekly() {
+        // Weekdays of May 2017; the 1st is a Monday.
+        let mut days = chart(&[0.0; 23]);
+        let weekdays = (1..=31).filter(|d| {
+            let date = NaiveDate::from_ymd_opt(2017, 5, *d).unwrap();
+            date.weekday().number_from_monday() <= 5
+        });
+        for (i, (point, day)) in days.iter_mut().zip(weekdays).enumerate() {
+            let price = 100.0 + i as f64;
+            point.date = format!("2017-05-{:02}", day);
+            point.open = price;
+            point.high = price + 2.0;
+            point.low = price - 1.0;
+            point.close = price + 0.5;
+            point.change = 1.0;
+            point.volume = 10.0;
+        }
+
+        let weeks = days.resample(ResamplePeriod::Weekly);
+        assert_eq!(weeks.len(), 5);
+        let dates: Vec<&str> = weeks.iter().map(|w| w.date.as_str()).collect();
+        assert_eq!(
+            dates,
+            [
+                "2017-05-01",
+                "2017-05-08",
+                "2017-05-15",
+                "2017-05-22",
+                "2017-05-29"
+            ]
+        );
+
+        let first = &weeks[0];
+        assert_eq!(first.open, 100.0);
+        assert_eq!(first.high, 106.0);
+        assert_eq!(first.low, 99.0);
+        assert_eq!(first.close, 104.5);
+        assert_eq!(first.volume, 50.0);
+        assert_eq!(first.change, 5.0);
+
+        let last = &weeks[4];
+        assert_eq!(last.open, 120.0);
+        assert_eq!(last.close, 122.5);
+        assert_eq!(last.volume, 30.0);
+
+        let months = days.resample(ResamplePeriod::Monthly);
+        assert_eq!(months.len(), 1);
+        assert_eq!(months[0].volume, 230.0);
+    }
+
+    #[test]
+    fn trade_vwap() {
+        let trade = |price, size| Trade {
+            price,
+            size,
+            trade_id: 0.0,
+            is_iso: false,
+            is_odd_lot: false,
+            is_outside_regular_hours: false,
+            is_single_price_cross: false,
+            is_trade_through_exempt: false,
+            timestamp: 0.0,
+        };
+        let trades = [trade(10.0, 100.0), trade(11.0, 300.0), trade(12.0, 100.0)];
+        assert_eq!(trades.trade_count(), 3);
+        assert_eq!(trades.total_volume(), 500.0);
+        // (1000 + 3300 + 1200) / 500
+        assert!((trades.vwap().unwrap() - 11.0).abs() < 1e-9);
+
+        let empty: Vec<Trade> = Vec::new();
+        assert_eq!(empty.vwap(), None);
+        assert_eq!(empty.trade_count(), 0);
+    }
+
+    #[test]
+    fn chart_columns() {
+        let mut points = chart(&[1.0, 2.0, 3.0]);
+        for (i, point) in points.iter_mut().enumerate() {
+            point.date = format!("2017-05-0{}", i + 1);
+            point.open = point.close - 0.5;
+            point.high = point.close + 1.0;
+            point.low = point.close - 1.0;
+            point.volume = 100.0 * (i + 1) as f64;
+        }
+
+        let columns = points.into_columns();
+        assert_eq!(columns.dates, ["2017-05-01", "2017-05-02", "2017-05-03"]);
+        assert_eq!(columns.opens, [0.5, 1.5, 2.5]);
+        assert_eq!(columns.highs, [2.0, 3.0, 4.0]);
+        assert_eq!(columns.lows, [0.0, 1.0, 2.0]);
+        assert_eq!(columns.closes, [1.0, 2.0, 3.0]);
+        assert_eq!(columns.volumes, [100.0, 200.0, 300.0]);
+        assert_eq!(Vec::new().into_columns(), ChartColumns::default());
+    }
+
+    #[test]
+    fn venue_ranking() {
+        let venue = |venue: &str, volume, effective_spread| EffectiveSpread {
+            volume,
+            venue: venue.to_string(),
+            venue_name: venue.to_string(),
+            effective_spread,
+            effective_quoted: 0.02,
+            price_improvement: 0.001,
+        };
+        let spreads = [
+            venue("XNYS", 17_000.0, 0.02),
+            venue("XNGS", 20_000.0, 0.01),
+            venue("EDGX", 500.0, 0.01),
+            venue("IEXG", 0.0, 0.001),
+            venue("BATS", 900.0, 0.03),
+            venue("ARCX", 1_000.0, 0.03),
+            venue("MEMX", 100.0, 0.0001),
+            venue("EPRL", 50.0, 0.5),
+        ];
+
+        assert_eq!(spreads.best_venue().unwrap().venue, "XNGS");
+        assert_eq!(spreads.worst_venue().unwrap().venue, "ARCX");
+
+        let empty: Vec<EffectiveSpread> = Vec::new();
+        assert!(empty.best_venue().is_none());
+        assert!(empty.worst_venue().is_none());
+        assert!(spreads[3..4].best_venue().is_none());
+    }
+}
@@ -52,6 +52,15 @@ case-sensitive and are found in the **Reference** section of each endpoint.
 Example: `?filter=symbol,volume,lastSalePrice` will return only the three
 fields specified.
 
+## Forward compatibility
+
+IEX adds fields to its responses without notice. In the default
+`Strictness::Lenient` mode, the response types ignore fields they don't know
+about, so new fields never break deserialization; they are only available
+through the untyped `Response`. This guarantee doesn't hold in
+`Strictness::Strict` mode, where the helpers it covers report new fields as
+errors, see `ClientBuilder::strictness`.
+
 ## WebSockets
 
 WebSocket support is limited at this time to Node.js server clients and
@@ -101,33 +110,150 @@ socket.on('disconnect', () => console.log('Disconnected.'))
 #[macro_use]
 extern crate serde_derive;
 extern crate chrono;
+extern crate chrono_tz;
+extern crate csv;
 extern crate failure;
+#[cfg(feature = "async")]
+extern crate futures;
+#[cfg(feature = "metrics")]
+extern crate metrics;
 extern crate reqwest;
+#[cfg(feature = "decimal")]
+extern crate rust_decimal;
 extern crate serde;
 extern crate serde_aux;
 extern crate serde_json;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "websocket")]
+extern crate tungstenite;
 
 use chrono::prelude::*;
 use failure::{Backtrace, Context, Fail, ResultExt};
 use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
 use serde_aux::prelude::*;
 use serde_json::Value;
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 use std::result;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Defines an enum of the codes IEX sends as strings, e.g. auction types,
+/// with an `Other` variant keeping the codes this crate doesn't know about.
+/// Each variant is listed with its code, and optionally other codes mapping
+/// to it: `News => "T1" | "T2"`. The enum gets an `as_str` method returning
+/// the code, the first one of variants with several, and is serialized and
+/// deserialized as that code.
+macro_rules! code_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $code:literal $(| $alias:literal)*,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            /// A code this crate doesn't know about.
+            Other(String),
+        }
 
+        impl $name {
+            /// Returns the code IEX uses for the value, the first one for
+            /// values with several codes.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $code,)*
+                    $name::Other(code) => code,
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let code = <String as serde::Deserialize>::deserialize(deserializer)?;
+                Ok(match code.as_str() {
+                    $($code $(| $alias)* => $name::$variant,)*
+                    _ => $name::Other(code),
+                })
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "async")]
+mod async_client;
+mod batch;
+#[cfg(feature = "blocking")]
+mod builder;
+#[cfg(feature = "blocking")]
+mod concurrency;
+mod deep;
+#[cfg(feature = "websocket")]
+mod deep_stream;
+#[cfg(test)]
+mod fixtures;
+mod indicators;
 mod market_data;
 mod markets;
+#[cfg(feature = "blocking")]
+mod poll;
 mod reference;
+mod retry;
+#[cfg(feature = "blocking")]
+mod schedule;
 mod stats;
 mod stocks;
+#[cfg(feature = "metrics")]
+mod telemetry;
+mod transport;
 mod types;
 
+pub mod market_hours;
+pub mod prelude;
+
+#[cfg(feature = "async")]
+pub use self::async_client::*;
+pub use self::batch::*;
+#[cfg(feature = "blocking")]
+pub use self::builder::*;
+pub use self::deep::*;
+#[cfg(feature = "websocket")]
+pub use self::deep_stream::*;
+pub use self::indicators::*;
 pub use self::market_data::*;
 pub use self::markets::*;
+#[cfg(feature = "blocking")]
+pub use self::poll::*;
 pub use self::reference::*;
+pub use self::retry::*;
 pub use self::stats::*;
 pub use self::stocks::*;
+pub use self::transport::*;
 pub use self::types::*;
 
 #[derive(Debug)]
@@ -160,6 +286,15 @@ impl From<&'static str> for MyError {
     }
 }
 
+// Allows writing `MyError::from(format!("oops: {}", detail))?`
+impl From<String> for MyError {
+    fn from(msg: String) -> MyError {
+        MyError {
+            inner: Context::new(msg),
+        }
+    }
+}
+
 // Allows adding more context via a String
 impl From<Context<String>> for MyError {
     fn from(inner: Context<String>) -> MyError {
@@ -176,6 +311,143 @@ impl From<Context<&'static str>> for MyError {
     }
 }
 
+/// Errors reported by the IEX API.
+#[derive(Debug, Clone)]
+pub enum IexError {
+    /// IEX doesn't know the requested symbol.
+    NotFound { symbol: String },
+    /// IEX answered with an HTML page instead of JSON, typically during
+    /// maintenance. `context` is the first line of the page.
+    ServiceUnavailable { context: String },
+    /// The HTTP request failed. `status` is the error status IEX answered
+    /// with, if any.
+    Http {
+        status: Option<u16>,
+        message: String,
+    },
+    /// IEX rejected the request because too many requests were made.
+    RateLimited,
+    /// The response isn't valid JSON or doesn't match the expected type.
+    Json { message: String },
+    /// IEX couldn't be reached: the DNS lookup or the connection failed.
+    Unreachable { message: String },
+}
+
+impl Fail for IexError {}
+
+impl Display for IexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IexError::NotFound { symbol } => write!(f, "unknown symbol: {}", symbol),
+            IexError::ServiceUnavailable { context } => {
+                write!(f, "IEX service unavailable: {}", context)
+            }
+            IexError::Http { message, .. } => write!(f, "HTTP request failed: {}", message),
+            IexError::RateLimited => write!(f, "rate limited by IEX"),
+            IexError::Json { message } => write!(f, "invalid JSON response: {}", message),
+            IexError::Unreachable { message } => write!(f, "IEX unreachable: {}", message),
+        }
+    }
+}
+
+impl From<reqwest::Error> for IexError {
+    fn from(err: reqwest::Error) -> IexError {
+        match err.status().map(|s| s.as_u16()) {
+            Some(404) => IexError::NotFound {
+                symbol: err.url().map(symbol_of_url).unwrap_or_default(),
+            },
+            Some(429) => IexError::RateLimited,
+            None if !err.is_timeout() && is_io_error(&err) => IexError::Unreachable {
+                message: err.to_string(),
+            },
+            status => IexError::Http {
+                status,
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+impl From<serde_json::Error> for IexError {
+    fn from(err: serde_json::Error) -> IexError {
+        IexError::Json {
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Returns a one-line message for `err` and its causes, outermost first,
+/// e.g. `could not fetch the quote of aapl: IEX unreachable: ...`. Causes
+/// whose message is already part of the message of an outer error, as with
+/// `with_context(|e| format!("...: {}", e))`, are left out. Unlike the
+/// `Debug` output of `failure::Error`, it never includes a backtrace, so it
+/// suits command-line tools reporting errors on stderr.
+pub fn user_message(err: &failure::Error) -> String {
+    let mut messages: Vec<String> = Vec::new();
+    for cause in err.iter_chain() {
+        let message = cause
+            .to_string()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !messages.iter().any(|outer| outer.contains(&message)) {
+            messages.push(message);
+        }
+    }
+    messages.join(": ")
+}
+
+/// Returns true if an I/O error, as raised by failed DNS lookups and
+/// connections, caused `err`.
+fn is_io_error(err: &reqwest::Error) -> bool {
+    let mut source = err
+        .get_ref()
+        .map(|e| e as &(dyn std::error::Error + 'static));
+    while let Some(e) = source {
+        if e.is::<std::io::Error>() {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+/// Returns the symbol of a `/stock/{symbol}/...` URL, or the whole URL for
+/// other endpoints.
+fn symbol_of_url(url: &reqwest::Url) -> String {
+    let mut segments = url.path_segments().into_iter().flatten();
+    match segments.position(|s| s == "stock").and(segments.next()) {
+        Some(symbol) => symbol.to_string(),
+        None => url.to_string(),
+    }
+}
+
+/// Returns `symbols` in uppercase, without duplicates, in their original
+/// order. Multi-symbol helpers send these and key their results by them.
+pub(crate) fn normalize_symbols(symbols: &[&str]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        let symbol = symbol.to_uppercase();
+        if !normalized.contains(&symbol) {
+            normalized.push(symbol);
+        }
+    }
+    normalized
+}
+
+/// Lookup of the results of multi-symbol helpers, e.g. `Client::quotes`,
+/// which are keyed by uppercase symbol, in any casing.
+pub trait SymbolLookup<V> {
+    /// Returns the value of `symbol`, ignoring its casing.
+    fn get_symbol(&self, symbol: &str) -> Option<&V>;
+}
+
+impl<V> SymbolLookup<V> for HashMap<String, V> {
+    fn get_symbol(&self, symbol: &str) -> Option<&V> {
+        self.get(&symbol.to_uppercase())
+    }
+}
+
 pub type Result<T> = result::Result<T, failure::Error>;
 
 /// IEX JSON Endpoint
@@ -184,14 +456,262 @@ const IEX_ENDPOINT: &str = "https://api.iextrading.com/1.0";
 /// IEX Webscoket Endpoint
 const IEX_WEBSOCKET_ENDPOINT: &str = "https://ws-api.iextrading.com/1.0";
 
+/// Plain-text bodies IEX returns instead of JSON for an unknown symbol.
+const NOT_FOUND_BODIES: [&str; 2] = ["Unknown symbol", "Not found"];
+
 /// `Client` acts as a Handler for the `Response` enum.
-#[derive(Default)]
-pub struct Client;
+///
+/// Use `ClientBuilder` to create a client with custom settings. Clones are
+/// cheap and share their configuration, symbol cache and in-flight
+/// requests, so a client can be cloned into every thread using it.
+#[cfg(feature = "blocking")]
+#[derive(Clone)]
+pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+/// Configuration and state shared by the clones of a `Client`.
+#[cfg(feature = "blocking")]
+struct ClientInner {
+    transport: Box<dyn Transport>,
+    /// Timeout of requests without an endpoint specific timeout.
+    timeout: Option<std::time::Duration>,
+    /// Timeouts keyed by `StocksEndpoint::token`.
+    endpoint_timeouts: HashMap<&'static str, std::time::Duration>,
+    /// Symbol list fetched from `/ref-data/symbols`, with the time it was
+    /// fetched.
+    symbols: Mutex<Option<(Instant, Arc<Symbols>)>>,
+    /// Requests in flight, shared by concurrent identical requests.
+    in_flight: SingleFlight,
+    /// Retries of transient errors, none when `None`.
+    retry: Option<RetryConfig>,
+    /// Retries left to all requests, unlimited when `None`.
+    retry_budget: Mutex<Option<Arc<RetryBudget>>>,
+    /// Whether requests go through `scheduler`.
+    fair_scheduling: AtomicBool,
+    scheduler: schedule::FairScheduler,
+    /// Whether quotes are requested with `displayPercent`.
+    display_percent: bool,
+    /// How strictly the typed helpers deserialize responses.
+    strictness: Strictness,
+    /// Headers sent along with every request.
+    default_headers: reqwest::header::HeaderMap,
+}
 
+#[cfg(feature = "blocking")]
+impl ClientInner {
+    fn new(transport: Box<dyn Transport>) -> Self {
+        ClientInner {
+            transport,
+            timeout: None,
+            endpoint_timeouts: HashMap::new(),
+            symbols: Mutex::new(None),
+            in_flight: SingleFlight::default(),
+            retry: None,
+            retry_budget: Mutex::new(None),
+            fair_scheduling: AtomicBool::new(false),
+            scheduler: schedule::FairScheduler::default(),
+            display_percent: false,
+            strictness: Strictness::Lenient,
+            default_headers: reqwest::header::HeaderMap::new(),
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
+
+#[cfg(feature = "blocking")]
 impl Client {
     /// Create a new Client.
     pub fn new() -> Self {
-        Client
+        Client::with_transport(HttpTransport::new())
+    }
+
+    /// Create a new Client issuing its requests through `transport`.
+    pub fn with_transport<T>(transport: T) -> Self
+    where
+        T: Transport + 'static,
+    {
+        Client::from_boxed_transport(Box::new(transport))
+    }
+
+    fn from_boxed_transport(transport: Box<dyn Transport>) -> Self {
+        Client::from_inner(ClientInner::new(transport))
+    }
+
+    fn from_inner(inner: ClientInner) -> Self {
+        Client {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Enables or disables fair scheduling, see
+    /// `ClientBuilder::fair_scheduling`.
+    ///
+    /// The setting is shared with the clones of the client.
+    pub fn with_fair_scheduling(self, enabled: bool) -> Self {
+        self.inner.fair_scheduling.store(enabled, Ordering::SeqCst);
+        self
+    }
+
+    /// Caps the retries of all requests to `budget`, see `RetryBudget`.
+    /// Requests are retried only if retries are enabled with
+    /// `ClientBuilder::retry`.
+    ///
+    /// The budget is shared with the clones of the client.
+    pub fn with_retry_budget(self, budget: RetryBudget) -> Self {
+        *self.inner.retry_budget.lock().unwrap() = Some(Arc::new(budget));
+        self
+    }
+
+    /// Returns true if quotes are requested with `displayPercent`, see
+    /// `ClientBuilder::display_percent`.
+    pub fn display_percent(&self) -> bool {
+        self.inner.display_percent
+    }
+
+    /// Returns how strictly the typed helpers deserialize responses, see
+    /// `ClientBuilder::strictness`.
+    pub fn strictness(&self) -> Strictness {
+        self.inner.strictness
+    }
+
+    /// Returns the DEEP and TOPS market data endpoints.
+    pub fn market_data(&self) -> market_data::MarketData<'_> {
+        market_data::MarketData { client: self }
+    }
+
+    /// Returns the market volume endpoints.
+    pub fn markets(&self) -> Markets<'_> {
+        Markets { client: self }
+    }
+
+    /// Returns the IEX stats endpoints.
+    pub fn iex_stats(&self) -> stats::Stats<'_> {
+        stats::Stats { client: self }
+    }
+
+    /// Returns the reference data endpoints.
+    pub fn reference_data(&self) -> ReferenceData<'_> {
+        ReferenceData { client: self }
+    }
+
+    /// Returns true if `symbol` is enabled for trading on IEX. Symbols are
+    /// matched case-insensitively against the cached `/ref-data/symbols`
+    /// list.
+    pub fn is_symbol_valid(&self, symbol: &str) -> Result<bool> {
+        let symbols = self.reference_data().cached_symbols()?;
+        Ok(symbols
+            .iter()
+            .any(|s| s.is_enabled && s.symbol.eq_ignore_ascii_case(symbol)))
+    }
+
+    /// Returns the timeout applied to requests to `endpoint`: its endpoint
+    /// specific timeout if one was set, the global one otherwise.
+    pub fn timeout_for(&self, endpoint: &StocksEndpoint) -> Option<std::time::Duration> {
+        self.inner
+            .endpoint_timeouts
+            .get(endpoint.token())
+            .cloned()
+            .or(self.inner.timeout)
+    }
+
+    /// Checks that IEX is reachable with a lightweight request, returning its
+    /// round-trip time.
+    ///
+    /// DNS and connection failures are reported as `IexError::Unreachable`,
+    /// error statuses as `IexError::RateLimited` or `IexError::Http`.
+    pub fn ping(&self) -> Result<std::time::Duration> {
+        let url = format!("{}/ref-data/symbols?filter=symbol&format=csv", IEX_ENDPOINT);
+        let started = Instant::now();
+        check_status(&self.get_raw(&url)?)?;
+        Ok(started.elapsed())
+    }
+
+    /// Performs a GET request for `url` and parses the JSON body.
+    fn get(&self, url: &str) -> Result<Response> {
+        parse_response(&self.get_raw(url)?)
+    }
+
+    /// Performs a GET request for `url`, leaving the body as is.
+    fn get_raw(&self, url: &str) -> Result<RawResponse> {
+        self.send(&self.request(url, self.inner.timeout))
+    }
+
+    /// Returns a request for `url` with the given `timeout` and the default
+    /// headers of the client.
+    fn request(&self, url: &str, timeout: Option<std::time::Duration>) -> Request {
+        Request::new(url)
+            .timeout(timeout)
+            .headers(self.inner.default_headers.clone())
+    }
+
+    /// Performs `req` through the transport, sharing the response with
+    /// identical requests already in flight.
+    fn send(&self, req: &Request) -> Result<RawResponse> {
+        self.inner.in_flight.get(req, || self.send_measured(req))
+    }
+
+    /// Same as `send_with_retries`, recording the request metrics.
+    #[cfg(feature = "metrics")]
+    fn send_measured(&self, req: &Request) -> Result<RawResponse> {
+        let started = Instant::now();
+        let result = self.send_with_retries(req);
+        telemetry::record_request(&req.url, &result, started.elapsed());
+        result
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    fn send_measured(&self, req: &Request) -> Result<RawResponse> {
+        self.send_with_retries(req)
+    }
+
+    /// Performs `req` through the transport, retrying transient errors as
+    /// configured by `ClientBuilder::retry`, as long as the retry budget
+    /// allows.
+    fn send_with_retries(&self, req: &Request) -> Result<RawResponse> {
+        let retry = match self.inner.retry {
+            Some(retry) => retry,
+            None => return self.send_once(req),
+        };
+        let budget = self.inner.retry_budget.lock().unwrap().clone();
+        let mut previous = None;
+        for attempt in 0.. {
+            let result = self.send_once(req);
+            if attempt >= retry.max_retries || !retry::is_retryable(&result) {
+                return result;
+            }
+            if let Some(ref budget) = budget {
+                if !budget.try_acquire() {
+                    return result;
+                }
+            }
+            let delay = retry.delay(attempt, previous);
+            #[cfg(feature = "tracing")]
+            retry::trace_retry(&req.url, attempt + 1, delay, &result);
+            std::thread::sleep(delay);
+            previous = Some(delay);
+        }
+        unreachable!()
+    }
+
+    /// Performs `req` through the transport, once it's its turn if fair
+    /// scheduling is enabled. The turn only lasts for this attempt, so that
+    /// other symbols are served while a retried request waits.
+    fn send_once(&self, req: &Request) -> Result<RawResponse> {
+        if !self.inner.fair_scheduling.load(Ordering::SeqCst) {
+            return self.inner.transport.get(req);
+        }
+        let symbol = reqwest::Url::parse(&req.url)
+            .map(|url| symbol_of_url(&url))
+            .unwrap_or_else(|_| req.url.clone());
+        let _permit = self.inner.scheduler.acquire(&symbol);
+        self.inner.transport.get(req)
     }
 
     /// stocks_request is the main entry-point to the IEX Stocks API.
@@ -199,17 +719,151 @@ impl Client {
     where
         S: Into<String>,
     {
-        let url = format!(
-            "{base}/stock/{symbol}/{endpoint}",
-            base = IEX_ENDPOINT,
-            symbol = symbol.into(),
-            endpoint = req.to_endpoint()
-        );
+        self.stocks_request_with(symbol, req, &RequestOptions::default())
+    }
+
+    /// Same as `stocks_request`, adding the query parameters of `opts`.
+    pub fn stocks_request_with<S>(
+        &self,
+        symbol: S,
+        req: StocksEndpoint,
+        opts: &RequestOptions,
+    ) -> Result<Response>
+    where
+        S: Into<String>,
+    {
+        let symbol = symbol.into();
+        let url = build_stock_url(IEX_ENDPOINT, &symbol, &req, opts);
+        let req = self.request(&url, self.timeout_for(&req));
+        parse_stocks_response(symbol, &self.send(&req)?)
+    }
+
+    /// Same as `stocks_request_with`, leaving the body unparsed so it can be
+    /// deserialized without building a `Value` first. Unknown symbols and
+    /// HTML bodies are still reported as errors.
+    pub(crate) fn stocks_request_raw(
+        &self,
+        symbol: String,
+        req: &StocksEndpoint,
+        opts: &RequestOptions,
+    ) -> Result<RawResponse> {
+        let url = build_stock_url(IEX_ENDPOINT, &symbol, req, opts);
+        let raw = self.send(&self.request(&url, self.timeout_for(req)))?;
+        check_stocks_response(symbol, &raw)?;
+        Ok(raw)
+    }
+}
+
+/// Parses the JSON body `raw` returned by a Stocks API request for `symbol`.
+fn parse_stocks_response(symbol: String, raw: &RawResponse) -> Result<Response> {
+    check_stocks_response(symbol, raw)?;
+    Ok(Response(
+        serde_json::from_str(&raw.body).map_err(IexError::from)?,
+    ))
+}
+
+/// Reports the error statuses and bodies of a Stocks API request for
+/// `symbol`, see `parse_stocks_response`. Unknown symbols are reported as
+/// `IexError::NotFound`.
+fn check_stocks_response(symbol: String, raw: &RawResponse) -> Result<()> {
+    if raw.status == 404 || NOT_FOUND_BODIES.contains(&raw.body.trim()) {
+        return Err(IexError::NotFound { symbol }.into());
+    }
+    check_body(raw)?;
+    check_status(raw)
+}
+
+/// Reports HTML bodies as `IexError::ServiceUnavailable`.
+fn check_body(raw: &RawResponse) -> Result<()> {
+    let body = raw.body.trim_start();
+    if body.starts_with('<') {
+        let context = body.lines().next().unwrap_or_default().trim().to_string();
+        return Err(IexError::ServiceUnavailable { context }.into());
+    }
+    Ok(())
+}
+
+/// Reports error statuses of `raw`: `429 Too Many Requests` as
+/// `IexError::RateLimited`, the others as `IexError::Http`, with the first
+/// line of the body as message.
+fn check_status(raw: &RawResponse) -> Result<()> {
+    if raw.status == 429 {
+        return Err(IexError::RateLimited.into());
+    }
+    if raw.status >= 400 {
+        return Err(IexError::Http {
+            status: Some(raw.status),
+            message: raw.body.lines().next().unwrap_or_default().to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Parses the JSON body of `raw`. HTML bodies are reported as
+/// `IexError::ServiceUnavailable`, error statuses as by `check_status` and
+/// invalid JSON as `IexError::Json`.
+fn parse_response(raw: &RawResponse) -> Result<Response> {
+    check_body(raw)?;
+    check_status(raw)?;
+    Ok(Response(
+        serde_json::from_str(&raw.body).map_err(IexError::from)?,
+    ))
+}
+
+/// How strictly the typed helpers deserialize responses, see
+/// `ClientBuilder::strictness`.
+///
+/// The strictness applies to the typed stock helpers of `Client` (`quote`,
+/// `company`, `stats`, ...), `Client::list`, `Client::quotes`,
+/// `Client::profile`, `MarketData::last`, `MarketData::last_snapshot`,
+/// `MarketData::trades` and the reference data helpers `symbols`,
+/// `symbols_csv` and `corporate_actions`. The other helpers, e.g. those of
+/// `Stats` and `Markets`, and `Response::try_into` always deserialize
+/// leniently. CSV lists have no unknown fields to report; only their blank
+/// and malformed numbers are checked.
+///
+/// Both modes accept numbers sent as strings, e.g. `"1.00"` for prices (see
+/// `from_number`), as IEX sends them either way. `money_to_f64` isn't
+/// affected either: it still converts the decimals `f64` can't represent
+/// to zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Fields the types don't know about are ignored, and blank or malformed
+    /// numbers sent as strings, e.g. `""` or `"N/A"`, read as zero.
+    #[default]
+    Lenient,
+    /// Fields the types don't know about and blank or malformed numbers are
+    /// errors, so that changes of the IEX payloads don't go unnoticed.
+    Strict,
+}
+
+thread_local! {
+    /// Strictness of the deserialization running on this thread, read by
+    /// the `deserialize_with` helpers like `from_str`.
+    static STRICTNESS: Cell<Strictness> = const { Cell::new(Strictness::Lenient) };
+}
+
+/// Restores the strictness of this thread when dropped, even if the
+/// deserialization panicked.
+struct StrictnessGuard(Strictness);
 
-        Ok(reqwest::get(&url)?.json()?)
+impl Drop for StrictnessGuard {
+    fn drop(&mut self) {
+        STRICTNESS.with(|s| s.set(self.0));
     }
 }
 
+/// Runs `f` with the deserialization helpers of this thread set to
+/// `strictness`.
+pub(crate) fn with_strictness<T, F>(strictness: Strictness, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _guard = StrictnessGuard(STRICTNESS.with(|s| s.replace(strictness)));
+    f()
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Response(pub Value);
 
@@ -221,6 +875,102 @@ impl Response {
     {
         Ok(serde_json::from_value(self.0)?)
     }
+
+    /// Same as `try_into`, deserializing as strictly as `strictness` says.
+    /// In strict mode, the first field of the response ignored by `T` is
+    /// reported as unknown, e.g. `bids.0.venue`.
+    pub fn try_into_with<T>(self, strictness: Strictness) -> Result<T>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        self.deserialize_with(strictness)
+    }
+
+    /// Same as `try_into_with`, without consuming the response.
+    pub(crate) fn deserialize_with<T>(&self, strictness: Strictness) -> Result<T>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        if strictness == Strictness::Lenient {
+            return Ok(with_strictness(strictness, || T::deserialize(&self.0))?);
+        }
+        let mut unknown = None;
+        let value = with_strictness(strictness, || {
+            serde_ignored::deserialize(&self.0, |path| {
+                unknown.get_or_insert_with(|| path.to_string());
+            })
+        })?;
+        if let Some(field) = unknown {
+            return Err(IexError::Json {
+                message: format!("unknown field `{}`", field),
+            }
+            .into());
+        }
+        Ok(value)
+    }
+
+    /// Deserializes a JSON array response. A `null` response is treated as
+    /// an empty array.
+    pub fn into_vec<T>(self) -> Result<Vec<T>>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        if self.0.is_null() {
+            return Ok(Vec::new());
+        }
+        self.try_into()
+    }
+
+    /// Same as `into_vec`, deserializing as strictly as `strictness` says,
+    /// see `try_into_with`.
+    pub fn into_vec_with<T>(self, strictness: Strictness) -> Result<Vec<T>>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        if self.0.is_null() {
+            return Ok(Vec::new());
+        }
+        self.try_into_with(strictness)
+    }
+
+    /// Returns the raw JSON of the response without consuming it, e.g. to
+    /// read fields the typed structs don't cover yet.
+    pub fn as_value(&self) -> &Value {
+        &self.0
+    }
+
+    /// Returns a copy of the raw JSON of the response.
+    pub fn clone_value(&self) -> Value {
+        self.0.clone()
+    }
+
+    /// Deserializes the response into `T` and applies `f` to it.
+    pub fn map<T, U, F>(self, f: F) -> Result<U>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+        F: FnOnce(T) -> U,
+    {
+        self.try_into().map(f)
+    }
+
+    /// Returns the field `name` of an object response, or `None` if the
+    /// response isn't an object or lacks the field.
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    /// Returns the JSON of the response, pretty-printed, e.g. to save it as
+    /// a fixture.
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(&self.0).unwrap_or_default()
+    }
+
+    /// Loads a response saved with `to_pretty_json`.
+    #[cfg(test)]
+    pub(crate) fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Response> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(Response(serde_json::from_str(&json)?))
+    }
 }
 
 pub trait Endpoint {
@@ -233,33 +983,288 @@ where
     T::Err: Display,
     D: Deserializer<'de>,
 {
-    let mut s = String::deserialize(deserializer)?;
-    if s.len() == 0 {
-        s = "0".to_string();
+    let s = String::deserialize(deserializer)?;
+    let strict = STRICTNESS.with(Cell::get) == Strictness::Strict;
+    if s.is_empty() && strict {
+        return Err(de::Error::custom("blank number"));
+    }
+    match T::from_str(&s) {
+        Ok(n) => Ok(n),
+        Err(_) if !strict => T::from_str("0").map_err(de::Error::custom),
+        Err(err) => Err(de::Error::custom(err)),
+    }
+}
+
+/// Deserializes a string with its whitespace trimmed and every run of
+/// whitespace collapsed to a single space, e.g. `" Consumer  Goods"` to
+/// `"Consumer Goods"`.
+pub fn from_collapsed_str<'de, D>(deserializer: D) -> result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Ok(s.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Serializes a value as a string, the counterpart of `from_str`.
+pub fn to_str<T, S>(value: &T, serializer: S) -> result::Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Deserializes a timestamp in milliseconds since midnight Jan 1, 1970.
+pub fn from_millis<'de, D>(deserializer: D) -> result::Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let millis = i64::deserialize(deserializer)?;
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| de::Error::custom(format!("invalid timestamp: {}", millis)))
+}
+
+/// Serializes a timestamp as milliseconds since midnight Jan 1, 1970, the
+/// counterpart of `from_millis`.
+pub fn to_millis<S>(value: &DateTime<Utc>, serializer: S) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(value.timestamp_millis())
+}
+
+/// Deserializes an ISO-8601 date and time without a timezone, e.g.
+/// `"2017-11-08T17:00:00"`. Empty strings map to `None`.
+pub fn from_naive_datetime_str<'de, D>(
+    deserializer: D,
+) -> result::Result<Option<NaiveDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    if s.is_empty() {
+        return Ok(None);
+    }
+    NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S")
+        .map(Some)
+        .map_err(de::Error::custom)
+}
+
+/// Serializes an optional date and time, the counterpart of
+/// `from_naive_datetime_str`. `None` serializes to an empty string.
+pub fn to_naive_datetime_str<S>(
+    value: &Option<NaiveDateTime>,
+    serializer: S,
+) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(dt) => serializer.collect_str(&dt.format("%Y-%m-%dT%H:%M:%S")),
+        None => serializer.serialize_str(""),
     }
-    T::from_str(&s).map_err(de::Error::custom)
 }
 
+/// Deserializes an ISO-8601 date, e.g. `"2017-11-10"`. Empty strings map to
+/// `None`.
+pub fn from_naive_date_str<'de, D>(deserializer: D) -> result::Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    if s.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+        .map(Some)
+        .map_err(de::Error::custom)
+}
+
+/// Serializes an optional date, the counterpart of `from_naive_date_str`.
+/// `None` serializes to an empty string.
+pub fn to_naive_date_str<S>(
+    value: &Option<NaiveDate>,
+    serializer: S,
+) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(date) => serializer.collect_str(&date.format("%Y-%m-%d")),
+        None => serializer.serialize_str(""),
+    }
+}
+
+/// Deserializes the stringified booleans IEX uses in its daily list files.
+///
+/// The tokens `""`, `"0"`, `"N"`, `"F"`, `"NO"` and `"FALSE"` map to `false`
+/// and `"1"`, `"Y"`, `"T"`, `"YES"` and `"TRUE"` map to `true`. Matching is
+/// case-insensitive; any other value is a deserialization error.
 pub fn from_bool_str<'de, T, D>(deserializer: D) -> result::Result<T, D::Error>
 where
     T: FromStr,
     T::Err: Display,
     D: Deserializer<'de>,
 {
-    let mut s = String::deserialize(deserializer)?;
-    if s == "N" || s == "F" || s == "" {
-        s = "false".to_string();
-    } else {
-        s = "true".to_string();
+    let s = String::deserialize(deserializer)?;
+    let b = match s.trim().to_uppercase().as_str() {
+        "" | "0" | "N" | "F" | "NO" | "FALSE" => "false",
+        "1" | "Y" | "T" | "YES" | "TRUE" => "true",
+        _ => {
+            return Err(de::Error::custom(format!(
+                "invalid boolean string: {:?}",
+                s
+            )))
+        }
+    };
+    T::from_str(b).map_err(de::Error::custom)
+}
+
+/// Serializes a boolean as `"Y"` or `"N"`, the counterpart of
+/// `from_bool_str`.
+pub fn to_bool_str<S>(value: &bool, serializer: S) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(if *value { "Y" } else { "N" })
+}
+
+/// Type of money amounts, e.g. prices: `f64`, or `rust_decimal::Decimal` with
+/// the `decimal` feature.
+#[cfg(not(feature = "decimal"))]
+pub type Money = f64;
+
+/// Type of money amounts, e.g. prices: `f64`, or `rust_decimal::Decimal` with
+/// the `decimal` feature.
+#[cfg(feature = "decimal")]
+pub type Money = rust_decimal::Decimal;
+
+/// Number IEX sends as a JSON integer, float or string, depending on the
+/// value, e.g. `1`, `1.0` or `"1.00"` for the same price.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LooseNumber {
+    Number(f64),
+    String(String),
+}
+
+impl LooseNumber {
+    fn into_f64<E: de::Error>(self) -> result::Result<f64, E> {
+        match self {
+            LooseNumber::Number(n) => Ok(n),
+            LooseNumber::String(s) => s
+                .trim()
+                .parse()
+                .map_err(|_| de::Error::custom(format!("invalid number: {:?}", s))),
+        }
+    }
+}
+
+/// Deserializes a number sent as a JSON integer, float or numeric string,
+/// e.g. `1`, `1.0` or `"1.00"`.
+pub fn from_number<'de, D>(deserializer: D) -> result::Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    LooseNumber::deserialize(deserializer)?.into_f64()
+}
+
+/// Deserializes a money amount sent as a JSON number, or as a numeric string
+/// like `from_number`.
+///
+/// With the `decimal` feature, the shortest decimal representation of the
+/// number is kept, so `142.37` is exactly `142.37`.
+pub fn from_money<'de, D>(deserializer: D) -> result::Result<Money, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[cfg(feature = "decimal")]
+    {
+        <Money as Deserialize>::deserialize(deserializer)
+    }
+    #[cfg(not(feature = "decimal"))]
+    {
+        from_number(deserializer)
     }
-    T::from_str(&s).map_err(de::Error::custom)
 }
 
-#[cfg(test)]
+/// Serializes a money amount as a JSON number, the counterpart of
+/// `from_money`.
+pub fn to_money<S>(value: &Money, serializer: S) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_f64(money_to_f64(*value))
+}
+
+/// Same as `from_money`, for optional amounts.
+pub fn from_opt_money<'de, D>(deserializer: D) -> result::Result<Option<Money>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[cfg(feature = "decimal")]
+    {
+        Option::<Money>::deserialize(deserializer)
+    }
+    #[cfg(not(feature = "decimal"))]
+    {
+        Option::<LooseNumber>::deserialize(deserializer)?
+            .map(LooseNumber::into_f64)
+            .transpose()
+    }
+}
+
+/// Same as `to_money`, for optional amounts.
+pub fn to_opt_money<S>(value: &Option<Money>, serializer: S) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => to_money(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Converts a money amount to `f64`, e.g. to compute ratios.
+pub fn money_to_f64(value: Money) -> f64 {
+    #[cfg(feature = "decimal")]
+    {
+        use rust_decimal::prelude::ToPrimitive;
+        value.to_f64().unwrap_or_default()
+    }
+    #[cfg(not(feature = "decimal"))]
+    {
+        value
+    }
+}
+
+/// Deserializes a boolean sent as the integer `1` or `0`, as some DEEP
+/// messages do. Any other value is a deserialization error.
+pub fn from_int_bool<'de, D>(deserializer: D) -> result::Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match i64::deserialize(deserializer)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        n => Err(de::Error::custom(format!("invalid integer boolean: {}", n))),
+    }
+}
+
+/// Serializes a boolean as `1` or `0`, the counterpart of `from_int_bool`.
+pub fn to_int_bool<S>(value: &bool, serializer: S) -> result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_u8(*value as u8)
+}
+
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
     use super::*;
 
-    static CLIENT: Client = Client;
     #[allow(non_upper_case_globals)]
     static symbol: &'static str = "aapl";
     #[allow(non_upper_case_globals)]
@@ -267,12 +1272,14 @@ mod tests {
 
     #[test]
     fn client_request_book() {
-        assert!(CLIENT.stocks_request(symbol, StocksEndpoint::Book).is_ok());
+        assert!(Client::new()
+            .stocks_request(symbol, StocksEndpoint::Book)
+            .is_ok());
     }
 
     #[test]
     fn client_request_chart() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(
                 symbol,
                 StocksEndpoint::Chart {
@@ -285,53 +1292,54 @@ mod tests {
 
     #[test]
     fn client_request_company() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::Company)
             .is_ok());
     }
 
     #[test]
     fn client_request_delayed_quote() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::DelayedQuote)
             .is_ok());
     }
 
     #[test]
     fn client_request_dividends() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::Dividends { duration })
             .is_ok());
     }
 
     #[test]
     fn client_request_earnings() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::Earnings)
             .is_ok());
     }
 
     #[test]
     fn client_request_effective_spread() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::EffectiveSpread)
             .is_ok());
     }
 
     #[test]
     fn client_request_financials() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::Financials)
             .is_ok());
     }
 
     #[test]
     fn client_request_list() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(
                 "market",
                 StocksEndpoint::List {
-                    param: ListParam::Gainers
+                    param: ListParam::Gainers,
+                    params: None,
                 }
             )
             .is_ok());
@@ -339,73 +1347,727 @@ mod tests {
 
     #[test]
     fn client_request_logo() {
-        assert!(CLIENT.stocks_request(symbol, StocksEndpoint::Logo).is_ok());
+        assert!(Client::new()
+            .stocks_request(symbol, StocksEndpoint::Logo)
+            .is_ok());
     }
 
     #[test]
     fn client_request_news() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::News { range: None })
             .is_ok());
     }
 
     #[test]
     fn client_request_ohlc() {
-        assert!(CLIENT.stocks_request(symbol, StocksEndpoint::Ohlc).is_ok());
+        assert!(Client::new()
+            .stocks_request(symbol, StocksEndpoint::Ohlc)
+            .is_ok());
     }
 
     #[test]
     fn client_request_peers() {
-        assert!(CLIENT.stocks_request(symbol, StocksEndpoint::Peers).is_ok());
+        assert!(Client::new()
+            .stocks_request(symbol, StocksEndpoint::Peers)
+            .is_ok());
     }
 
     #[test]
     fn client_request_previous() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::Previous)
             .is_ok());
     }
 
     #[test]
     fn client_request_price() {
-        assert!(CLIENT.stocks_request(symbol, StocksEndpoint::Price).is_ok());
+        assert!(Client::new()
+            .stocks_request(symbol, StocksEndpoint::Price)
+            .is_ok());
     }
 
     #[test]
     fn client_request_quote() {
-        assert!(CLIENT.stocks_request(symbol, StocksEndpoint::Quote).is_ok());
+        assert!(Client::new()
+            .stocks_request(symbol, StocksEndpoint::Quote)
+            .is_ok());
     }
 
     #[test]
     fn client_request_relevant() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::Relevant)
             .is_ok());
     }
 
     #[test]
     fn client_request_splits() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::Splits { duration })
             .is_ok());
     }
 
     #[test]
     fn client_request_stats() {
-        assert!(CLIENT.stocks_request(symbol, StocksEndpoint::Stats).is_ok());
+        assert!(Client::new()
+            .stocks_request(symbol, StocksEndpoint::Stats)
+            .is_ok());
     }
 
     #[test]
     fn client_request_threshold_securities() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request("market", StocksEndpoint::ThresholdSecurities { date: None })
             .is_ok());
     }
 
     #[test]
     fn client_request_volume_by_venue() {
-        assert!(CLIENT
+        assert!(Client::new()
             .stocks_request(symbol, StocksEndpoint::VolumeByVenue)
             .is_ok());
     }
+
+    #[test]
+    fn symbol_validity() {
+        let url = format!("{}/ref-data/symbols", IEX_ENDPOINT);
+        let json_data = r#"[
+            {"symbol":"A","name":"Agilent Technologies Inc.","date":"2018-10-23","isEnabled":true,"type":"cs","iexId":"2"},
+            {"symbol":"AAPL","name":"Apple Inc.","date":"2018-10-23","isEnabled":true,"type":"cs","iexId":"11"},
+            {"symbol":"ZZZ","name":"Disabled Corp.","date":"2018-10-23","isEnabled":false,"type":"cs","iexId":"99"}
+        ]"#;
+        let transport = Arc::new(MockTransport::new().with(&url, json_data));
+        let client = Client::with_transport(transport.clone());
+
+        assert!(client.is_symbol_valid("AAPL").unwrap());
+        assert!(client.is_symbol_valid("aapl").unwrap());
+        assert!(!client.is_symbol_valid("ZZZ").unwrap());
+        assert!(!client.is_symbol_valid("MSFT").unwrap());
+        assert_eq!(transport.hits(&url), 1);
+    }
+
+    #[test]
+    fn client_is_send_sync_and_clone() {
+        fn assert_send_sync<T: Send + Sync + Clone>() {}
+        assert_send_sync::<Client>();
+
+        let transport = Arc::new(["aapl", "fb", "msft", "ibm"].iter().fold(
+            MockTransport::new(),
+            |t, sym| {
+                let url = format!("{}/stock/{}/quote", IEX_ENDPOINT, sym);
+                t.with(&url, fixtures::QUOTE)
+            },
+        ));
+        let client = Client::with_transport(transport.clone());
+
+        let handles: Vec<_> = ["aapl", "fb", "msft", "ibm"]
+            .iter()
+            .map(|sym| {
+                let client = client.clone();
+                std::thread::spawn(move || client.quote(sym))
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap().is_ok());
+        }
+        assert_eq!(transport.requests().len(), 4);
+        assert!(Arc::ptr_eq(&client.inner, &client.clone().inner));
+    }
+
+    #[test]
+    fn fair_scheduling_serves_symbols_round_robin() {
+        let transport =
+            Arc::new(MockTransport::new().with_delay(std::time::Duration::from_millis(100)));
+        let client = ClientBuilder::new()
+            .transport(transport.clone())
+            .fair_scheduling(true)
+            .build();
+        let url =
+            |sym: &str, endpoint: &str| format!("{}/stock/{}/{}", IEX_ENDPOINT, sym, endpoint);
+
+        std::thread::scope(|s| {
+            let client = &client;
+            s.spawn(move || client.stocks_request("busy", StocksEndpoint::Quote));
+            while transport.requests().is_empty() {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            let waiting = [
+                ("aapl", StocksEndpoint::Quote),
+                ("aapl", StocksEndpoint::Company),
+                ("aapl", StocksEndpoint::Logo),
+                ("fb", StocksEndpoint::Quote),
+                ("fb", StocksEndpoint::Company),
+                ("snap", StocksEndpoint::Quote),
+            ];
+            for (i, (sym, endpoint)) in waiting.iter().cloned().enumerate() {
+                s.spawn(move || client.stocks_request(sym, endpoint));
+                while client.inner.scheduler.waiting() < i + 1 {
+                    std::thread::sleep(std::time::Duration::from_millis(1));
+                }
+            }
+        });
+
+        let served: Vec<_> = transport.requests().into_iter().map(|r| r.url).collect();
+        assert_eq!(
+            served,
+            [
+                url("busy", "quote"),
+                url("aapl", "quote"),
+                url("fb", "quote"),
+                url("snap", "quote"),
+                url("aapl", "company"),
+                url("fb", "company"),
+                url("aapl", "logo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_fair_scheduling_is_shared_with_clones() {
+        let client = Client::with_transport(MockTransport::new());
+        let _clone = client.clone().with_fair_scheduling(true);
+        assert!(client.inner.fair_scheduling.load(Ordering::SeqCst));
+
+        let client = client.with_fair_scheduling(false);
+        assert!(!client.inner.fair_scheduling.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn fair_scheduling_releases_the_turn_between_retries() {
+        let aapl = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let fb = format!("{}/stock/fb/quote", IEX_ENDPOINT);
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_status(&aapl, 503, "")
+                .with(&fb, fixtures::QUOTE),
+        );
+        let client = ClientBuilder::new()
+            .transport(transport.clone())
+            .retry(RetryConfig {
+                max_retries: 1,
+                base_delay: std::time::Duration::from_millis(200),
+                max_delay: std::time::Duration::from_millis(200),
+                jitter: JitterStrategy::None,
+            })
+            .fair_scheduling(true)
+            .build();
+
+        std::thread::scope(|s| {
+            let client = &client;
+            s.spawn(move || client.quote("aapl"));
+            while transport.hits(&aapl) == 0 {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            // Served while the aapl request sleeps before its retry.
+            assert!(client.quote("fb").is_ok());
+            assert_eq!(transport.hits(&aapl), 1);
+        });
+        assert_eq!(transport.hits(&aapl), 2);
+    }
+
+    #[test]
+    fn concurrent_identical_requests_are_deduplicated() {
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let transport = Arc::new(
+            MockTransport::new()
+                .with(&url, fixtures::QUOTE)
+                .with_delay(std::time::Duration::from_millis(200)),
+        );
+        let client = Client::with_transport(transport.clone());
+
+        std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4).map(|_| s.spawn(|| client.quote("aapl"))).collect();
+            for handle in handles {
+                assert_eq!(handle.join().unwrap().unwrap().symbol, "AAPL");
+            }
+        });
+        assert_eq!(transport.hits(&url), 1);
+    }
+
+    #[test]
+    fn response_as_value() {
+        let resp = Response(serde_json::json!({
+            "url": "https://storage.googleapis.com/iex/api/logos/AAPL.png",
+            "undocumented": 42
+        }));
+        assert_eq!(resp.as_value()["undocumented"], 42);
+        assert_eq!(resp.clone_value()["undocumented"], 42);
+
+        let logo: Logo = resp.try_into().unwrap();
+        assert_eq!(
+            logo.url,
+            "https://storage.googleapis.com/iex/api/logos/AAPL.png"
+        );
+    }
+
+    #[test]
+    fn response_map_and_field() {
+        let resp = Response(serde_json::from_str(fixtures::QUOTE).unwrap());
+        assert_eq!(resp.field("symbol"), Some(&Value::from("AAPL")));
+        assert_eq!(resp.field("undocumented"), None);
+        assert_eq!(Response(serde_json::json!([1, 2])).field("symbol"), None);
+
+        let name = resp.map(|quote: Quote| quote.symbol).unwrap();
+        assert_eq!(name, "AAPL");
+
+        let resp = Response(serde_json::json!({"url": 42}));
+        assert!(resp.map(|logo: Logo| logo.url).is_err());
+    }
+
+    #[test]
+    fn response_into_vec() {
+        let resp = Response(serde_json::json!([{"url": "a.png"}, {"url": "b.png"}]));
+        let logos: Vec<Logo> = resp.into_vec().unwrap();
+        assert_eq!(logos.len(), 2);
+        assert_eq!(logos[1].url, "b.png");
+
+        let resp = Response(serde_json::json!([]));
+        assert!(resp.into_vec::<Logo>().unwrap().is_empty());
+
+        let resp = Response(Value::Null);
+        assert!(resp.into_vec::<Logo>().unwrap().is_empty());
+
+        let resp = Response(serde_json::json!({"url": "a.png"}));
+        assert!(resp.into_vec::<Logo>().is_err());
+    }
+
+    #[test]
+    fn unknown_symbol() {
+        let url = format!("{}/stock/zzzz/quote", IEX_ENDPOINT);
+        let transport = MockTransport::new().with_status(&url, 404, "Unknown symbol");
+        let client = Client::with_transport(transport);
+
+        let err = client
+            .stocks_request("zzzz", StocksEndpoint::Quote)
+            .unwrap_err();
+        match err.downcast_ref::<IexError>() {
+            Some(IexError::NotFound { symbol: s }) => assert_eq!(s, "zzzz"),
+            _ => panic!("expected IexError::NotFound, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn maintenance_page() {
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let html =
+            "\n<html><head><title>IEX is down for maintenance</title></head>\n<body></body></html>";
+        let client = Client::with_transport(MockTransport::new().with(&url, html));
+
+        let err = client
+            .stocks_request("aapl", StocksEndpoint::Quote)
+            .unwrap_err();
+        match err.downcast_ref::<IexError>() {
+            Some(IexError::ServiceUnavailable { context }) => assert_eq!(
+                context,
+                "<html><head><title>IEX is down for maintenance</title></head>"
+            ),
+            _ => panic!("expected IexError::ServiceUnavailable, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn rate_limited_stocks_request() {
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let client = Client::with_transport(MockTransport::new().with_status(
+            &url,
+            429,
+            "Too Many Requests",
+        ));
+
+        let err = client
+            .stocks_request("aapl", StocksEndpoint::Quote)
+            .unwrap_err();
+        match err.downcast_ref::<IexError>() {
+            Some(IexError::RateLimited) => {}
+            _ => panic!("expected IexError::RateLimited, got {:?}", err),
+        }
+    }
+
+    fn status_error(status: u16) -> IexError {
+        let resp = http::Response::builder().status(status).body("").unwrap();
+        reqwest::Response::from(resp)
+            .error_for_status()
+            .unwrap_err()
+            .into()
+    }
+
+    #[test]
+    fn iex_error_from_reqwest() {
+        match status_error(404) {
+            IexError::NotFound { .. } => {}
+            err => panic!("expected IexError::NotFound, got {:?}", err),
+        }
+        match status_error(429) {
+            IexError::RateLimited => {}
+            err => panic!("expected IexError::RateLimited, got {:?}", err),
+        }
+        match status_error(500) {
+            IexError::Http {
+                status: Some(500), ..
+            } => {}
+            err => panic!("expected IexError::Http, got {:?}", err),
+        }
+
+        let err = reqwest::Client::new().get("not a url").send().unwrap_err();
+        match IexError::from(err) {
+            IexError::Http { status: None, .. } => {}
+            err => panic!("expected IexError::Http, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn iex_error_unreachable() {
+        // Nothing listens on port 1, so the connection is refused.
+        let err = reqwest::Client::new()
+            .get("http://127.0.0.1:1/")
+            .send()
+            .unwrap_err();
+        match IexError::from(err) {
+            IexError::Unreachable { .. } => {}
+            err => panic!("expected IexError::Unreachable, got {:?}", err),
+        }
+    }
+
+    struct UnreachableTransport;
+
+    impl Transport for UnreachableTransport {
+        fn get(&self, _: &Request) -> Result<RawResponse> {
+            Err(IexError::Unreachable {
+                message: "dns error".to_string(),
+            }
+            .into())
+        }
+    }
+
+    #[test]
+    fn ping() {
+        let url = format!("{}/ref-data/symbols?filter=symbol&format=csv", IEX_ENDPOINT);
+        let transport = Arc::new(MockTransport::new().with(&url, "symbol\nA\nAAPL\n"));
+        let client = Client::with_transport(transport.clone());
+        assert!(client.ping().is_ok());
+        assert_eq!(transport.hits(&url), 1);
+
+        let client = Client::with_transport(MockTransport::new().with_status(&url, 503, "down"));
+        match client.ping().unwrap_err().downcast::<IexError>() {
+            Ok(IexError::Http {
+                status: Some(503), ..
+            }) => {}
+            err => panic!("expected IexError::Http, got {:?}", err),
+        }
+
+        let client = Client::with_transport(UnreachableTransport);
+        match client.ping().unwrap_err().downcast::<IexError>() {
+            Ok(IexError::Unreachable { .. }) => {}
+            err => panic!("expected IexError::Unreachable, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn iex_error_from_serde_json() {
+        let err = serde_json::from_str::<Quote>("{").unwrap_err();
+        match IexError::from(err) {
+            IexError::Json { .. } => {}
+            err => panic!("expected IexError::Json, got {:?}", err),
+        }
+    }
+
+    /// Deserializes `json` into a `T` after adding a field unknown to the
+    /// crate.
+    fn parse_with_unknown_field<T: serde::de::DeserializeOwned>(json: &str) -> T {
+        let mut value: Value = serde_json::from_str(json).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .insert("newUndocumentedField".to_string(), 1.into());
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        parse_with_unknown_field::<Quote>(fixtures::QUOTE);
+        parse_with_unknown_field::<CorporateActionsData>(fixtures::CORPORATE_ACTION);
+        parse_with_unknown_field::<AuctionData>(fixtures::AUCTION);
+        parse_with_unknown_field::<SymbolData>(
+            r#"{"symbol":"A","name":"Agilent Technologies Inc.","date":"2018-10-23",
+                "isEnabled":true,"type":"cs","iexId":"2"}"#,
+        );
+        parse_with_unknown_field::<Company>(
+            r#"{"symbol":"AAPL","companyName":"Apple Inc.","exchange":"Nasdaq Global Select",
+                "industry":"Computer Hardware","website":"http://www.apple.com",
+                "description":"","CEO":"Timothy D. Cook","issueType":"cs",
+                "sector":"Technology"}"#,
+        );
+        parse_with_unknown_field::<ChartDataPoint>(
+            r#"{"date":"2017-04-03","open":143.1192,"high":143.5275,"low":142.4619,
+                "close":143.1092,"volume":19985714,"unadjustedVolume":19985714,
+                "change":0.039835,"changePercent":0.028,"vwap":143.0507,
+                "label":"Apr 03, 17","changeOverTime":-0.0039}"#,
+        );
+        parse_with_unknown_field::<Trade>(
+            r#"{"price":156.1,"size":100,"tradeId":517341294,"isISO":false,
+                "isOddLot":false,"isOutsideRegularHours":false,
+                "isSinglePriceCross":false,"isTradeThroughExempt":false,
+                "timestamp":1494619192003}"#,
+        );
+        parse_with_unknown_field::<EffectiveSpread>(
+            r#"{"volume":4899,"venue":"XCHI","venueName":"CHX","effectiveSpread":0.02,
+                "effectiveQuoted":0.02,"priceImprovement":0.0}"#,
+        );
+        parse_with_unknown_field::<OHLC>(
+            r#"{"open":{"price":154,"time":1506605400394},
+                "close":{"price":153.28,"time":1506605400394},"high":154.8,"low":153.25}"#,
+        );
+        parse_with_unknown_field::<LastSale>(
+            r#"{"symbol":"SNAP","price":111.76,"size":5,"time":1480446905681}"#,
+        );
+    }
+
+    #[test]
+    fn from_number_accepts_ints_floats_and_strings() {
+        #[derive(Deserialize)]
+        struct Price {
+            #[serde(deserialize_with = "from_number")]
+            price: f64,
+        }
+        for json in &[r#"{"price":1}"#, r#"{"price":1.0}"#, r#"{"price":"1.00"}"#] {
+            let p: Price = serde_json::from_str(json).unwrap();
+            assert_eq!(p.price, 1.0);
+        }
+        assert!(serde_json::from_str::<Price>(r#"{"price":"one"}"#).is_err());
+        assert!(serde_json::from_str::<Price>(r#"{"price":true}"#).is_err());
+    }
+
+    #[test]
+    fn quote_prices_as_int_float_or_string() {
+        for price in &["222", "222.0", r#""222.00""#] {
+            let json = fixtures::QUOTE.replace("222.73", price);
+            let quote: Quote = serde_json::from_str(&json).unwrap();
+            assert_eq!(money_to_f64(quote.latest_price), 222.0);
+            assert_eq!(quote.high.map(money_to_f64), Some(223.0));
+        }
+    }
+
+    #[test]
+    fn strictness_of_blank_numbers() {
+        let json_data = r#"{"symbol":"A","name":"Agilent Technologies Inc.",
+            "date":"2018-10-23","isEnabled":true,"type":"cs","iexId":""}"#;
+        let response = || Response(serde_json::from_str(json_data).unwrap());
+
+        let s: SymbolData = response().try_into_with(Strictness::Lenient).unwrap();
+        assert_eq!(s.iex_id, 0);
+        let err = response()
+            .try_into_with::<SymbolData>(Strictness::Strict)
+            .unwrap_err();
+        assert!(err.to_string().contains("blank number"), "{}", err);
+    }
+
+    #[test]
+    fn strictness_of_malformed_numbers() {
+        for iex_id in &["abc", "N/A"] {
+            let json_data = format!(
+                r#"{{"symbol":"A","name":"Agilent Technologies Inc.",
+                "date":"2018-10-23","isEnabled":true,"type":"cs","iexId":"{}"}}"#,
+                iex_id
+            );
+            let response = || Response(serde_json::from_str(&json_data).unwrap());
+
+            let s: SymbolData = response().try_into_with(Strictness::Lenient).unwrap();
+            assert_eq!(s.iex_id, 0);
+            let err = response()
+                .try_into_with::<SymbolData>(Strictness::Strict)
+                .unwrap_err();
+            assert!(err.to_string().contains("invalid digit"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn strictness_of_unknown_fields() {
+        let url = format!("{}/stock/aapl/logo", IEX_ENDPOINT);
+        let transport = Arc::new(
+            MockTransport::new()
+                .with(
+                    &url,
+                    r#"{"url":"https://example.com/aapl.png","format":"png"}"#,
+                )
+                .with(
+                    &format!("{}/stock/aapl/quote", IEX_ENDPOINT),
+                    fixtures::QUOTE,
+                ),
+        );
+        let client = |strictness| {
+            ClientBuilder::new()
+                .transport(transport.clone())
+                .strictness(strictness)
+                .build()
+        };
+
+        assert!(client(Strictness::Lenient).logo("aapl").is_ok());
+        let strict = client(Strictness::Strict);
+        let err = strict.logo("aapl").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `format`"),
+            "{}",
+            err
+        );
+        assert!(strict.quote("aapl").is_ok());
+    }
+
+    #[test]
+    fn strictness_is_restored_after_a_panic() {
+        let result = std::panic::catch_unwind(|| {
+            with_strictness(Strictness::Strict, || panic!("deserialization bug"))
+        });
+        assert!(result.is_err());
+        assert_eq!(STRICTNESS.with(Cell::get), Strictness::Lenient);
+    }
+
+    #[test]
+    fn pretty_json_fixture_round_trip() {
+        let response = Response(serde_json::from_str(fixtures::QUOTE).unwrap());
+        let path = std::env::temp_dir().join(format!("iex-quote-{}.json", std::process::id()));
+        std::fs::write(&path, response.to_pretty_json()).unwrap();
+        let loaded = Response::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.0, response.0);
+        assert!(response
+            .to_pretty_json()
+            .contains("\n  \"symbol\": \"AAPL\""));
+        assert!(Response::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn symbols_are_normalized() {
+        assert_eq!(
+            normalize_symbols(&["aapl", "FB", "AAPL", "Aapl"]),
+            ["AAPL", "FB"]
+        );
+
+        let mut prices = HashMap::new();
+        prices.insert("AAPL".to_string(), 222.73);
+        assert_eq!(prices.get_symbol("aapl"), Some(&222.73));
+        assert_eq!(prices.get_symbol("AAPL"), Some(&222.73));
+        assert_eq!(prices.get_symbol("fb"), None);
+    }
+
+    #[test]
+    fn my_error_from_string() {
+        let err = MyError::from(format!("no quote for {}", "aapl"));
+        assert_eq!(err.to_string(), "no quote for aapl");
+    }
+
+    #[test]
+    fn user_message_is_one_line() {
+        let err: failure::Error = IexError::ServiceUnavailable {
+            context: "<html>\n<title>Maintenance</title>".to_string(),
+        }
+        .context("could not fetch the quote of aapl".to_string())
+        .into();
+        let msg = user_message(&err);
+        assert_eq!(
+            msg,
+            "could not fetch the quote of aapl: IEX service unavailable: <html> <title>Maintenance</title>"
+        );
+        assert!(!msg.contains('\n'));
+    }
+
+    #[test]
+    fn user_message_skips_causes_already_in_the_context() {
+        let result: Result<()> = Err(IexError::RateLimited.into());
+        let err: failure::Error = result
+            .with_context(|e| format!("could not fetch the quote of aapl: {}", e))
+            .unwrap_err()
+            .into();
+        assert_eq!(
+            user_message(&err),
+            format!(
+                "could not fetch the quote of aapl: {}",
+                IexError::RateLimited
+            )
+        );
+    }
+
+    #[test]
+    fn symbol_of_stock_url() {
+        let url = reqwest::Url::parse("https://api.iextrading.com/1.0/stock/aapl/quote").unwrap();
+        assert_eq!(symbol_of_url(&url), "aapl");
+        let url = reqwest::Url::parse("https://api.iextrading.com/1.0/ref-data/symbols").unwrap();
+        assert_eq!(symbol_of_url(&url), url.to_string());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn exact_decimal_money() {
+        use std::str::FromStr;
+
+        #[derive(Deserialize, Serialize)]
+        struct Price {
+            #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+            price: Money,
+        }
+
+        let p: Price = serde_json::from_str(r#"{"price":142.37}"#).unwrap();
+        assert_eq!(p.price, rust_decimal::Decimal::from_str("142.37").unwrap());
+        assert_eq!(p.price.to_string(), "142.37");
+        assert_eq!(serde_json::to_string(&p).unwrap(), r#"{"price":142.37}"#);
+
+        let quote: Quote = serde_json::from_str(crate::fixtures::QUOTE).unwrap();
+        assert_eq!(quote.latest_price.to_string(), "222.73");
+        assert_eq!(quote.to_string(), "AAPL 222.73 +1.23 (+0.56%) vol 32112345");
+    }
+
+    #[derive(Deserialize)]
+    struct Flag {
+        #[serde(deserialize_with = "from_bool_str")]
+        flag: bool,
+    }
+
+    fn parse_flag(s: &str) -> serde_json::Result<bool> {
+        let json_data = format!(r#"{{"flag":"{}"}}"#, s);
+        serde_json::from_str::<Flag>(&json_data).map(|f| f.flag)
+    }
+
+    #[test]
+    fn bool_str_false_tokens() {
+        for s in &["", "0", "N", "F", "NO", "FALSE", "n", "no", "False"] {
+            assert!(!parse_flag(s).unwrap(), "{:?} should be false", s);
+        }
+    }
+
+    #[test]
+    fn bool_str_true_tokens() {
+        for s in &["1", "Y", "T", "YES", "TRUE", "y", "yes", "True"] {
+            assert!(parse_flag(s).unwrap(), "{:?} should be true", s);
+        }
+    }
+
+    #[test]
+    fn bool_str_invalid_token() {
+        assert!(parse_flag("maybe").is_err());
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct IntFlag {
+        #[serde(deserialize_with = "from_int_bool", serialize_with = "to_int_bool")]
+        flag: bool,
+    }
+
+    fn parse_int_flag(s: &str) -> serde_json::Result<bool> {
+        serde_json::from_str::<IntFlag>(&format!(r#"{{"flag":{}}}"#, s)).map(|f| f.flag)
+    }
+
+    #[test]
+    fn int_bool() {
+        assert!(parse_int_flag("1").unwrap());
+        assert!(!parse_int_flag("0").unwrap());
+        assert!(parse_int_flag("2").is_err());
+        assert!(parse_int_flag("-1").is_err());
+        assert!(parse_int_flag("true").is_err());
+        assert_eq!(
+            serde_json::to_string(&IntFlag { flag: true }).unwrap(),
+            r#"{"flag":1}"#
+        );
+    }
 }
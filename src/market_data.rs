@@ -1,20 +1,52 @@
 /*!
  */
 
-use super::Result;
+#[cfg(feature = "blocking")]
+use super::Client;
+use super::{
+    from_millis, from_money, normalize_symbols, to_millis, to_money, Money, MyError, Result, Trade,
+    IEX_ENDPOINT,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 use std::collections::HashMap;
 
 pub type Auctions = HashMap<String, AuctionData>;
 
+/// Side of the unpaired shares of an auction.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImbalanceSide {
+    /// Buy-side imbalance.
+    Buy,
+    /// Sell-side imbalance.
+    Sell,
+    /// No imbalance, or a side this crate doesn't know about.
+    #[serde(rename = "None", other)]
+    NoImbalance,
+}
+
+code_enum! {
+    /// Type of an IEX auction.
+    pub enum AuctionType {
+        Open => "Open",
+        Close => "Close",
+        Halt => "Halt",
+        Volatility => "Volatility",
+        #[allow(clippy::upper_case_acronyms)]
+        IPO => "IPO",
+    }
+}
+
 /// DEEP broadcasts an Auction Information message every one second between the
 /// Lock-in Time and the auction match for Opening and Closing Auctions, and
 /// during the Display Only Period for IPO, Halt, and Volatility Auctions.
 /// Only IEX listed securities are eligible for IEX Auctions.
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct AuctionData {
     /// Auction type (Open, Close, Halt, Volatility, IPO).
-    auction_type: String,
+    auction_type: AuctionType,
     /// Number of shares paired at the reference price using orders on the
     /// auction book.
     paired_shares: u64,
@@ -22,37 +54,103 @@ pub struct AuctionData {
     /// auction book.
     imbalance_shares: u64,
     /// Which side is unbalanced using orders on the auction book.
-    imbalance_side: String,
+    imbalance_side: ImbalanceSide,
     /// Clearing price at or within the reference price range using orders on
     /// the auction book
-    reference_price: u64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    reference_price: Money,
     /// Clearing price using eligible auction orders.
-    indicative_price: u64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    indicative_price: Money,
     /// Clearing price using orders on the auction book.
-    auction_book_price: u64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    auction_book_price: Money,
     /// Reference price used for the auction collar, if any.
-    collar_reference_price: u64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    collar_reference_price: Money,
     /// Lower threshold price of the auction collar, if any.
-    lower_collar_price: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    lower_collar_price: Money,
     /// Upper threshold price of the auction collar, if any.
-    upper_collar_price: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    upper_collar_price: Money,
     /// Number of extensions an auction has received.
     extension_number: u64,
-    /// Projected time of the auction match. Formatted as HH:MM:SS.
-    start_time: u64,
+    /// Projected time of the auction match.
+    #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+    start_time: DateTime<Utc>,
     /// Timestamp of the auction information.
-    timestamp: u64,
+    #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+    timestamp: DateTime<Utc>,
 }
 
-pub struct MarketData;
+/// Official opening or closing price of a symbol, as reported by
+/// `/deep/official-price`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OfficialPrice {
+    /// Price type of the official price (`Open` or `Close`).
+    pub price_type: String,
+    /// Official opening or closing price.
+    pub price: f64,
+    /// Time of the official price.
+    #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Last sale of a symbol, as reported by `/tops/last`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LastSale {
+    pub symbol: String,
+    /// Price of the last sale.
+    pub price: f64,
+    /// Size of the last sale.
+    pub size: u64,
+    /// Time of the last sale.
+    #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+    pub time: DateTime<Utc>,
+}
 
-impl MarketData {
+#[cfg(feature = "blocking")]
+pub struct MarketData<'a> {
+    pub(crate) client: &'a Client,
+}
+
+#[cfg(feature = "blocking")]
+impl<'a> MarketData<'a> {
     pub fn tops(&self) -> Result<()> {
         Ok(())
     }
 
-    pub fn last(&self) -> Result<()> {
-        Ok(())
+    /// Returns the last sale of every symbol traded on IEX.
+    pub fn last(&self) -> Result<Vec<LastSale>> {
+        self.client
+            .get(&format!("{}/tops/last", IEX_ENDPOINT))?
+            .into_vec_with(self.client.strictness())
+    }
+
+    /// Returns the last sale of every symbol traded on IEX, keyed by
+    /// uppercase symbol, e.g. to look up many symbols from one request.
+    ///
+    /// The snapshot covers every symbol listed on IEX, close to 9,000, i.e.
+    /// around 700 KB of JSON; prefer `Client::quotes` for a few symbols.
+    pub fn last_snapshot(&self) -> Result<HashMap<String, LastSale>> {
+        Ok(self
+            .last()?
+            .into_iter()
+            .map(|sale| (sale.symbol.to_uppercase(), sale))
+            .collect())
+    }
+
+    /// Returns the time of the latest sale across the market, a cheap signal
+    /// of whether IEX is actively trading. Fails if no symbol has traded.
+    pub fn last_system_time(&self) -> Result<DateTime<Utc>> {
+        self.last()?
+            .into_iter()
+            .map(|sale| sale.time)
+            .max()
+            .ok_or_else(|| MyError::from("no sales in the /tops/last snapshot").into())
     }
 
     pub fn hist(&self) -> Result<()> {
@@ -67,8 +165,43 @@ impl MarketData {
         Ok(())
     }
 
-    pub fn trades(&self) -> Result<()> {
-        Ok(())
+    /// Returns the recent trades of `symbols` on IEX, keyed by uppercase
+    /// symbol.
+    pub fn trades(&self, symbols: &[&str]) -> Result<HashMap<String, Vec<Trade>>> {
+        let url = format!(
+            "{}/deep/trades?symbols={}",
+            IEX_ENDPOINT,
+            normalize_symbols(symbols).join(",")
+        );
+        let trades: HashMap<String, Vec<Trade>> = self
+            .client
+            .get(&url)?
+            .try_into_with(self.client.strictness())?;
+        Ok(trades
+            .into_iter()
+            .map(|(symbol, trades)| (symbol.to_uppercase(), trades))
+            .collect())
+    }
+
+    /// Returns the recent trades of `symbols` of at least `min_size` shares,
+    /// keyed by symbol. DEEP can't filter by size, so all recent trades are
+    /// fetched and filtered here. Symbols without such trades are left out.
+    pub fn block_trades(
+        &self,
+        symbols: &[&str],
+        min_size: u64,
+    ) -> Result<HashMap<String, Vec<Trade>>> {
+        let min_size = min_size as f64;
+        Ok(self
+            .trades(symbols)?
+            .into_iter()
+            .map(|(symbol, trades)| {
+                let blocks: Vec<Trade> =
+                    trades.into_iter().filter(|t| t.size >= min_size).collect();
+                (symbol, blocks)
+            })
+            .filter(|(_, blocks)| !blocks.is_empty())
+            .collect())
     }
 
     pub fn system_event(&self) -> Result<()> {
@@ -99,37 +232,195 @@ impl MarketData {
         Ok(())
     }
 
-    pub fn official_price(&self) -> Result<()> {
-        Ok(())
+    /// Returns the latest official opening or closing price of `symbols`,
+    /// keyed by uppercase symbol. Symbols without an official price are left
+    /// out.
+    pub fn official_price(&self, symbols: &[&str]) -> Result<HashMap<String, OfficialPrice>> {
+        let url = format!(
+            "{}/deep/official-price?symbols={}",
+            IEX_ENDPOINT,
+            normalize_symbols(symbols).join(",")
+        );
+        let prices: HashMap<String, Value> = self.client.get(&url)?.try_into()?;
+        Ok(prices
+            .into_iter()
+            .filter_map(|(symbol, price)| {
+                serde_json::from_value(price)
+                    .ok()
+                    .map(|price| (symbol.to_uppercase(), price))
+            })
+            .collect())
+    }
+
+    /// Returns the official closing price of `symbols`, keyed by symbol.
+    /// Symbols whose closing auction hasn't published a price yet are left
+    /// out.
+    pub fn closing_prices(&self, symbols: &[&str]) -> Result<HashMap<String, f64>> {
+        Ok(self
+            .official_price(symbols)?
+            .into_iter()
+            .filter(|(_, p)| p.price_type == "Close")
+            .map(|(symbol, p)| (symbol, p.price))
+            .collect())
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
     use super::*;
+    use crate::fixtures::AUCTION;
+    use crate::{MockTransport, SymbolLookup};
 
     #[test]
-    fn deserialize_auction_hash() {
+    fn last_system_time() {
+        let url = format!("{}/tops/last", IEX_ENDPOINT);
+        let json_data = r#"[
+            {"symbol": "SNAP", "price": 11.05, "size": 100, "time": 1540411200125},
+            {"symbol": "FB", "price": 154.05, "size": 10, "time": 1540411201337},
+            {"symbol": "AIG+", "price": 21.52, "size": 100, "time": 1540411199000}
+        ]"#;
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let time = client.market_data().last_system_time().unwrap();
+        assert_eq!(time.timestamp_millis(), 1540411201337);
+
+        let client = Client::with_transport(MockTransport::new().with(&url, "[]"));
+        assert!(client.market_data().last_system_time().is_err());
+    }
+
+    #[test]
+    fn last_snapshot() {
+        let url = format!("{}/tops/last", IEX_ENDPOINT);
+        let json_data = r#"[
+            {"symbol": "SNAP", "price": 11.05, "size": 100, "time": 1540411200125},
+            {"symbol": "FB", "price": 154.05, "size": 10, "time": 1540411201337},
+            {"symbol": "AIG+", "price": 21.52, "size": 100, "time": 1540411199000}
+        ]"#;
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let sales = client.market_data().last_snapshot().unwrap();
+        assert_eq!(sales.len(), 3);
+        assert_eq!(sales["FB"].price, 154.05);
+        assert_eq!(sales["AIG+"].size, 100);
+        assert_eq!(
+            sales.get_symbol("snap").unwrap().time.timestamp_millis(),
+            1540411200125
+        );
+    }
+
+    #[test]
+    fn block_trades() {
+        let url = format!("{}/deep/trades?symbols=SNAP,FB", IEX_ENDPOINT);
+        let trade = |id, size| {
+            format!(
+                r#"{{"price": 156.1, "size": {}, "tradeId": {}, "isISO": false,
+                    "isOddLot": false, "isOutsideRegularHours": false,
+                    "isSinglePriceCross": false, "isTradeThroughExempt": false,
+                    "timestamp": 1494619192003}}"#,
+                size, id
+            )
+        };
+        let json_data = format!(
+            r#"{{"SNAP": [{}, {}, {}], "FB": [{}]}}"#,
+            trade(1, 100),
+            trade(2, 10000),
+            trade(3, 25000),
+            trade(4, 300)
+        );
+        let client = Client::with_transport(MockTransport::new().with(&url, &json_data));
+
+        let blocks = client
+            .market_data()
+            .block_trades(&["snap", "FB", "Snap"], 10000)
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+        let ids: Vec<f64> = blocks["SNAP"].iter().map(|t| t.trade_id).collect();
+        assert_eq!(ids, [2.0, 3.0]);
+    }
+
+    #[test]
+    fn closing_prices() {
+        let url = format!("{}/deep/official-price?symbols=AAPL,FB", IEX_ENDPOINT);
         let json_data = r#"{
-            "ZIEXT": {
-                "auctionType":"Close",
-                "pairedShares":2000,
-                "imbalanceShares":0,
-                "imbalanceSide":"None",
-                "referencePrice":1,
-                "indicativePrice":1,
-                "auctionBookPrice":1,
-                "collarReferencePrice":1,
-                "lowerCollarPrice":0.5,
-                "upperCollarPrice":1.5,
-                "extensionNumber":0,
-                "startTime":1540324800000,
-                "timestamp":1540324799126
-            }
+            "AAPL": {"priceType": "Close", "price": 222.73, "timestamp": 1540411200000},
+            "FB": {"priceType": "Open", "price": 154.39, "timestamp": 1540388000000}
         }"#;
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let prices = client
+            .market_data()
+            .closing_prices(&["aapl", "fb"])
+            .unwrap();
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices["AAPL"], 222.73);
+    }
+
+    #[test]
+    fn deserialize_auction_hash() {
+        let json_data = format!(r#"{{"ZIEXT": {}}}"#, AUCTION);
 
         let m: Auctions = serde_json::from_str(&json_data).unwrap();
         let ad = &m["ZIEXT"];
-        assert_eq!(ad.auction_type, "Close");
+        assert_eq!(ad.auction_type, AuctionType::Close);
+        assert_eq!(ad.timestamp.timestamp_millis(), 1540324799126);
+    }
+
+    #[test]
+    fn auction_type() {
+        let types: Vec<AuctionType> =
+            serde_json::from_str(r#"["Open", "Close", "Halt", "Volatility", "IPO", "Closing"]"#)
+                .unwrap();
+        assert_eq!(
+            types,
+            vec![
+                AuctionType::Open,
+                AuctionType::Close,
+                AuctionType::Halt,
+                AuctionType::Volatility,
+                AuctionType::IPO,
+                AuctionType::Other("Closing".to_string()),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&AuctionType::Other("Closing".to_string())).unwrap(),
+            r#""Closing""#
+        );
+    }
+
+    #[test]
+    fn imbalance_side() {
+        let sides: Vec<ImbalanceSide> =
+            serde_json::from_str(r#"["Buy", "Sell", "None", "Both"]"#).unwrap();
+        assert_eq!(
+            sides,
+            vec![
+                ImbalanceSide::Buy,
+                ImbalanceSide::Sell,
+                ImbalanceSide::NoImbalance,
+                ImbalanceSide::NoImbalance
+            ]
+        );
+
+        let ad: AuctionData = serde_json::from_str(AUCTION).unwrap();
+        assert_eq!(ad.imbalance_side, ImbalanceSide::NoImbalance);
+    }
+
+    #[test]
+    fn auction_prices_as_int_float_or_string() {
+        for price in &["10", "10.0", r#""10.00""#] {
+            let json_data = AUCTION.replace("10.05", price);
+            let ad: AuctionData = serde_json::from_str(&json_data).unwrap();
+            assert_eq!(crate::money_to_f64(ad.reference_price), 10.0);
+            assert_eq!(crate::money_to_f64(ad.collar_reference_price), 10.0);
+        }
+        let json_data = AUCTION.replace("10.05", r#""n/a""#);
+        assert!(serde_json::from_str::<AuctionData>(&json_data).is_err());
+    }
+
+    #[test]
+    fn auction_round_trip() {
+        let json_data: Value = serde_json::from_str(AUCTION).unwrap();
+        let ad: AuctionData = serde_json::from_value(json_data.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&ad).unwrap(), json_data);
     }
 }
@@ -0,0 +1,255 @@
+/*! Market hours
+
+Regular trading hours of the US equity markets, 9:30 a.m. to 4:00 p.m.
+Eastern Time on weekdays, except on the holidays observed by the NYSE:
+
+* New Year's Day
+* Martin Luther King, Jr. Day
+* Washington's Birthday
+* Good Friday
+* Memorial Day
+* Juneteenth National Independence Day (since 2022)
+* Independence Day
+* Labor Day
+* Thanksgiving Day
+* Christmas Day
+
+Holidays falling on a Sunday are observed the following Monday and those
+falling on a Saturday the preceding Friday, except New Year's Day which is then
+not observed. Early closes, e.g. the day after Thanksgiving, are not accounted
+for.
+
+```
+use chrono::{TimeZone, Utc};
+use iex::market_hours;
+
+// Wednesday, October 24 2018 at 11:25 a.m. EDT
+let now = Utc.with_ymd_and_hms(2018, 10, 24, 15, 25, 0).unwrap();
+assert!(market_hours::is_market_open(now));
+```
+ */
+
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::America::New_York;
+
+/// Time the market opens, in Eastern Time.
+fn open_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 30, 0).unwrap()
+}
+
+/// Time the market closes, in Eastern Time.
+fn close_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(16, 0, 0).unwrap()
+}
+
+/// Returns true if the market is open at `now`.
+pub fn is_market_open(now: DateTime<Utc>) -> bool {
+    let local = now.with_timezone(&New_York);
+    is_trading_day(local.date_naive()) && local.time() >= open_time() && local.time() < close_time()
+}
+
+/// Returns the first time the market opens after `now`. While the market
+/// is open, that is the open of the next trading day.
+pub fn next_open(now: DateTime<Utc>) -> DateTime<Utc> {
+    let local = now.with_timezone(&New_York);
+    let mut date = local.date_naive();
+    if local.time() >= open_time() {
+        date = date.succ_opt().unwrap();
+    }
+    while !is_trading_day(date) {
+        date = date.succ_opt().unwrap();
+    }
+    New_York
+        .from_local_datetime(&date.and_time(open_time()))
+        .single()
+        .expect("9:30 a.m. ET is never ambiguous")
+        .with_timezone(&Utc)
+}
+
+/// Converts a date and a time of day in Eastern Time, e.g. from fields IEX
+/// documents "in ET", to UTC, accounting for daylight saving time.
+///
+/// During the hour repeated when DST ends, the earlier (EDT) instant is
+/// returned. Times skipped when DST starts are read as standard time, so
+/// 2:30 a.m. is 3:30 a.m. EDT.
+pub fn et_time_to_utc(date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    match New_York.from_local_datetime(&local) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t.with_timezone(&Utc),
+        LocalResult::None => Utc.from_utc_datetime(&(local + Duration::hours(5))),
+    }
+}
+
+/// Returns true if the market has a regular session on `date`.
+pub fn is_trading_day(date: NaiveDate) -> bool {
+    match date.weekday() {
+        Weekday::Sat | Weekday::Sun => false,
+        _ => !is_holiday(date),
+    }
+}
+
+/// Returns true if the market is closed for a holiday on `date`.
+pub fn is_holiday(date: NaiveDate) -> bool {
+    let year = date.year();
+    let mut holidays = vec![
+        nth_weekday(year, 1, Weekday::Mon, 3),
+        nth_weekday(year, 2, Weekday::Mon, 3),
+        easter(year) - Duration::days(2),
+        last_weekday(year, 5, Weekday::Mon),
+        observed(ymd(year, 7, 4)),
+        nth_weekday(year, 9, Weekday::Mon, 1),
+        nth_weekday(year, 11, Weekday::Thu, 4),
+        observed(ymd(year, 12, 25)),
+    ];
+    if year >= 2022 {
+        holidays.push(observed(ymd(year, 6, 19)));
+    }
+    // New Year's Day isn't observed on the preceding Friday.
+    let new_year = ymd(year, 1, 1);
+    if new_year.weekday() != Weekday::Sat {
+        holidays.push(observed(new_year));
+    }
+    holidays.contains(&date)
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// Moves a holiday falling on a weekend to the closest weekday.
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date - Duration::days(1),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+/// Returns the `n`th `weekday` of `month`, starting at 1.
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u32) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8).unwrap()
+}
+
+/// Returns the last `weekday` of `month`.
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    let next_month = if month == 12 {
+        ymd(year + 1, 1, 1)
+    } else {
+        ymd(year, month + 1, 1)
+    };
+    let mut date = next_month.pred_opt().unwrap();
+    while date.weekday() != weekday {
+        date = date.pred_opt().unwrap();
+    }
+    date
+}
+
+/// Returns the date of Easter Sunday in the Gregorian calendar.
+fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    ymd(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn mid_session() {
+        // 11:25 a.m. EDT (UTC-4).
+        assert!(is_market_open(utc(2018, 10, 24, 15, 25)));
+        // 9:29 a.m. EST (UTC-5), 4:00 p.m. EST.
+        assert!(!is_market_open(utc(2018, 12, 3, 14, 29)));
+        assert!(is_market_open(utc(2018, 12, 3, 14, 30)));
+        assert!(!is_market_open(utc(2018, 12, 3, 21, 0)));
+    }
+
+    #[test]
+    fn weekend() {
+        assert!(!is_market_open(utc(2018, 10, 27, 15, 0)));
+        assert_eq!(
+            next_open(utc(2018, 10, 27, 15, 0)),
+            utc(2018, 10, 29, 13, 30)
+        );
+    }
+
+    #[test]
+    fn holidays() {
+        // Thanksgiving, Christmas and Good Friday 2018.
+        assert!(!is_market_open(utc(2018, 11, 22, 16, 0)));
+        assert!(!is_market_open(utc(2018, 12, 25, 16, 0)));
+        assert!(is_holiday(ymd(2018, 3, 30)));
+        // Independence Day 2020 falls on a Saturday.
+        assert!(is_holiday(ymd(2020, 7, 3)));
+        // New Year's Day 2022 falls on a Saturday and isn't observed.
+        assert!(!is_holiday(ymd(2021, 12, 31)));
+        assert!(is_holiday(ymd(2022, 6, 20)));
+        assert!(!is_holiday(ymd(2021, 6, 18)));
+        assert!(is_holiday(ymd(2018, 5, 28)));
+    }
+
+    #[test]
+    fn next_open_after_holiday() {
+        // Christmas Eve 2018 after the close: the 25th is a holiday.
+        assert_eq!(
+            next_open(utc(2018, 12, 24, 22, 0)),
+            utc(2018, 12, 26, 14, 30)
+        );
+        // Before the open on a trading day.
+        assert_eq!(
+            next_open(utc(2018, 10, 24, 12, 0)),
+            utc(2018, 10, 24, 13, 30)
+        );
+        // During the session.
+        assert_eq!(
+            next_open(utc(2018, 10, 24, 15, 0)),
+            utc(2018, 10, 25, 13, 30)
+        );
+    }
+
+    #[test]
+    fn et_to_utc_across_dst() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        // DST starts on March 11 2018 and ends on November 4 2018.
+        assert_eq!(
+            et_time_to_utc(ymd(2018, 3, 10), time(9, 30)),
+            utc(2018, 3, 10, 14, 30)
+        );
+        assert_eq!(
+            et_time_to_utc(ymd(2018, 3, 12), time(9, 30)),
+            utc(2018, 3, 12, 13, 30)
+        );
+        assert_eq!(
+            et_time_to_utc(ymd(2018, 3, 11), time(2, 30)),
+            utc(2018, 3, 11, 7, 30)
+        );
+        assert_eq!(
+            et_time_to_utc(ymd(2018, 11, 4), time(1, 30)),
+            utc(2018, 11, 4, 5, 30)
+        );
+        assert_eq!(
+            et_time_to_utc(ymd(2018, 11, 5), time(16, 0)),
+            utc(2018, 11, 5, 21, 0)
+        );
+    }
+}
@@ -51,14 +51,23 @@ The above example will return JSON with the following keys
 ```
 */
 
-use super::Result;
-
-pub struct Markets;
+#[cfg(feature = "blocking")]
+use super::concurrency::join;
+#[cfg(feature = "blocking")]
+use super::Client;
+use super::{from_millis, to_millis, ListParam, Quote, Result, StocksEndpoint, IEX_ENDPOINT};
+use chrono::{DateTime, Utc};
+use std::thread;
+
+#[cfg(feature = "blocking")]
+pub struct Markets<'a> {
+  pub(crate) client: &'a Client,
+}
 
 pub type Market = Vec<MarketData>;
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct MarketData {
   /// Market Identifer Code (MIC)
   mic: String,
@@ -76,12 +85,128 @@ pub struct MarketData {
   tape_c: u64,
   /// Venue's percentage of shares traded in the market.
   market_percent: f64,
-  /// Last update time of the data in milliseconds since midnigt Jan 1, 1970.
-  last_updated: u64, // TODO(markcol): convert from UNIX Epoch to timestamp
+  /// Last update time of the data.
+  #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+  last_updated: DateTime<Utc>,
 }
 
-impl Markets {
+/// Venue volumes and top movers, fetched together by `Markets::snapshot`.
+/// Each section keeps its own result so one failed request doesn't hide the
+/// others.
+#[derive(Debug)]
+pub struct MarketSnapshot {
+  /// Traded volume per venue.
+  pub market: Result<Market>,
+  /// Quotes of the top 10 gainers.
+  pub gainers: Result<Vec<Quote>>,
+  /// Quotes of the top 10 losers.
+  pub losers: Result<Vec<Quote>>,
+}
+
+#[cfg(feature = "blocking")]
+impl<'a> Markets<'a> {
+  /// Returns the traded volume per venue.
   pub fn market(&self) -> Result<Market> {
-    Ok(Vec::new())
+    self.client.get(&format!("{}/market", IEX_ENDPOINT))?.into_vec()
+  }
+
+  /// Fetches the traded volume per venue and the top gainers and losers
+  /// concurrently.
+  ///
+  /// Returns an error only if all three requests fail; otherwise the
+  /// failures are reported per section.
+  pub fn snapshot(&self) -> Result<MarketSnapshot> {
+    let (market, gainers, losers) = thread::scope(|s| {
+      let market = s.spawn(|| self.market());
+      let gainers = s.spawn(|| self.list(ListParam::Gainers));
+      let losers = s.spawn(|| self.list(ListParam::Losers));
+      (join(market), join(gainers), join(losers))
+    });
+
+    match (market, gainers, losers) {
+      (Err(e), Err(_), Err(_)) => Err(e),
+      (market, gainers, losers) => Ok(MarketSnapshot {
+        market,
+        gainers,
+        losers,
+      }),
+    }
+  }
+
+  fn list(&self, param: ListParam) -> Result<Vec<Quote>> {
+    self.client.list(param, None)
+  }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+  use super::*;
+  use crate::fixtures::QUOTE;
+  use crate::MockTransport;
+  use serde_json::Value;
+
+  const MARKET: &str = r#"[{
+    "mic": "TRF",
+    "tapeId": "-",
+    "venueName": "TRF Volume",
+    "volume": 589171705,
+    "tapeA": 305187928,
+    "tapeB": 119650027,
+    "tapeC": 164333750,
+    "marketPercent": 0.37027,
+    "lastUpdated": 1480433817317
+  }]"#;
+
+  #[test]
+  fn snapshot() {
+    let quotes = format!("[{}]", QUOTE);
+    let transport = MockTransport::new()
+      .with(&format!("{}/market", IEX_ENDPOINT), MARKET)
+      .with(&format!("{}/stock/market/list/gainers", IEX_ENDPOINT), &quotes)
+      .with(&format!("{}/stock/market/list/losers", IEX_ENDPOINT), "[]");
+    let client = Client::with_transport(transport);
+
+    let snapshot = client.markets().snapshot().unwrap();
+    let market = snapshot.market.unwrap();
+    assert_eq!(market.len(), 1);
+    assert_eq!(market[0].mic, "TRF");
+    assert_eq!(snapshot.gainers.unwrap()[0].symbol, "AAPL");
+    assert!(snapshot.losers.unwrap().is_empty());
+  }
+
+  #[test]
+  fn snapshot_partial_failure() {
+    let transport = MockTransport::new().with(&format!("{}/market", IEX_ENDPOINT), MARKET);
+    let client = Client::with_transport(transport);
+
+    let snapshot = client.markets().snapshot().unwrap();
+    assert!(snapshot.market.is_ok());
+    assert!(snapshot.gainers.is_err());
+    assert!(snapshot.losers.is_err());
+
+    let client = Client::with_transport(MockTransport::new());
+    assert!(client.markets().snapshot().is_err());
+  }
+
+  #[test]
+  fn market_round_trip() {
+    let json_data: Value = serde_json::from_str(
+      r#"{
+        "mic": "TRF",
+        "tapeId": "-",
+        "venueName": "TRF Volume",
+        "volume": 589171705,
+        "tapeA": 305187928,
+        "tapeB": 119650027,
+        "tapeC": 164333750,
+        "marketPercent": 0.37027,
+        "lastUpdated": 1480433817317
+      }"#,
+    )
+    .unwrap();
+
+    let md: MarketData = serde_json::from_value(json_data.clone()).unwrap();
+    assert_eq!(md.last_updated.timestamp_millis(), 1480433817317);
+    assert_eq!(serde_json::to_value(&md).unwrap(), json_data);
   }
 }
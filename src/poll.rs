@@ -0,0 +1,107 @@
+/*! Polling
+
+Fetches quotes on a fixed cadence, for callers wanting live-ish data without
+the WebSocket API.
+ */
+
+use super::{Client, Quote, Result};
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Iterator returned by `Client::poll_quotes`.
+pub struct QuotePoller<'a> {
+    client: &'a Client,
+    symbols: Vec<String>,
+    interval: Duration,
+    next_tick: Option<Instant>,
+}
+
+impl<'a> Iterator for QuotePoller<'a> {
+    type Item = Result<HashMap<String, Quote>>;
+
+    /// Waits for the next tick, then fetches the quotes. Ticks which passed
+    /// while the previous fetch was still running are skipped rather than
+    /// fired back to back.
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(tick) = self.next_tick {
+            let now = Instant::now();
+            if tick > now {
+                thread::sleep(tick - now);
+            }
+        }
+
+        let started = Instant::now();
+        let symbols: Vec<&str> = self.symbols.iter().map(|s| s.as_str()).collect();
+        let quotes = self.client.quotes(&symbols);
+
+        let now = Instant::now();
+        let mut tick = started + self.interval;
+        while tick < now && self.interval > Duration::from_secs(0) {
+            tick += self.interval;
+        }
+        self.next_tick = Some(tick);
+
+        Some(quotes)
+    }
+}
+
+impl Client {
+    /// Returns an endless iterator fetching the quotes of `symbols` every
+    /// `interval`. The first batch is fetched immediately.
+    ///
+    /// The client has no rate limiter: the poller only paces the fetches by
+    /// `interval`, and a tick is skipped while the previous fetch is still
+    /// running. Each fetch is a `quotes` request, so it is retried and
+    /// scheduled like any other request of the client, and a rate limited
+    /// fetch is yielded as `IexError::RateLimited`. Pick an `interval`
+    /// within your IEX plan's limits.
+    pub fn poll_quotes(&self, symbols: &[&str], interval: Duration) -> QuotePoller<'_> {
+        QuotePoller {
+            client: self,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            interval,
+            next_tick: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::batch_url;
+    use crate::fixtures::QUOTE;
+    use crate::{BatchFilters, BatchType, IexError, MockTransport};
+    use std::sync::Arc;
+
+    #[test]
+    fn poll_quotes() {
+        let url = batch_url(&["AAPL"], &[BatchType::Quote], &BatchFilters::new());
+        let json_data = format!(r#"{{"AAPL":{{"quote":{}}}}}"#, QUOTE);
+        let transport = Arc::new(MockTransport::new().with(&url, &json_data));
+        let client = Client::with_transport(transport.clone());
+
+        let interval = Duration::from_millis(20);
+        let started = Instant::now();
+        let ticks: Vec<_> = client.poll_quotes(&["aapl"], interval).take(3).collect();
+
+        assert!(started.elapsed() >= interval * 2);
+        assert_eq!(ticks.len(), 3);
+        for quotes in ticks {
+            assert_eq!(quotes.unwrap()["AAPL"].symbol, "AAPL");
+        }
+        assert_eq!(transport.hits(&url), 3);
+    }
+
+    #[test]
+    fn poll_quotes_yields_rate_limited_ticks() {
+        let url = batch_url(&["AAPL"], &[BatchType::Quote], &BatchFilters::new());
+        let client = Client::with_transport(MockTransport::new().with_status(&url, 429, ""));
+
+        let mut poller = client.poll_quotes(&["aapl"], Duration::from_millis(1));
+        match poller.next().unwrap().unwrap_err().downcast::<IexError>() {
+            Ok(IexError::RateLimited) => {}
+            err => panic!("expected IexError::RateLimited, got {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+/*! Prelude
+
+Re-exports the commonly used types so they can be imported in one go:
+
+```
+use iex::prelude::*;
+```
+
+The deserialization helpers (`from_str`, `from_bool_str`, ...) are
+intentionally left out.
+ */
+
+#[cfg(feature = "async")]
+pub use super::async_client::AsyncClient;
+pub use super::batch::{BatchFilters, BatchRequest, BatchType, SymbolProfile};
+#[cfg(feature = "blocking")]
+pub use super::builder::ClientBuilder;
+pub use super::deep::{BookBuilder, BookSide, BookUpdate, DeepBook, DeepMessage};
+#[cfg(feature = "websocket")]
+pub use super::deep_stream::DeepStream;
+pub use super::indicators::{
+    ChartColumns, ChartSeries, IntoChartColumns, ResamplePeriod, TradeSeries, VenueRanking,
+};
+pub use super::market_data::{
+    AuctionData, AuctionType, Auctions, ImbalanceSide, LastSale, OfficialPrice,
+};
+#[cfg(feature = "blocking")]
+pub use super::markets::Markets;
+pub use super::markets::{Market, MarketSnapshot};
+#[cfg(feature = "blocking")]
+pub use super::reference::ReferenceData;
+pub use super::reference::{
+    CommonIssueType, CorporateActions, CorporateActionsData, DailyDividendData, DailyDividends,
+    DailyListFormat, DailyListKind, IssueEvent, NextDayExDateData, NextDayExDates, SymbolChange,
+    SymbolData, SymbolDiff, SymbolDirectory, SymbolDirectoryData, Symbols,
+};
+pub use super::retry::{JitterStrategy, RetryBudget, RetryConfig};
+pub use super::stats::{
+    HistoricalStats, IntradayStat, IntradayStats, RecentStats, RecordStat, RecordStats,
+    StatsDashboard,
+};
+pub use super::stocks::{
+    ChartParams, Duration, ListParam, ListParams, NewsCount, RequestOptions, StocksEndpoint,
+};
+pub use super::types::*;
+#[cfg(feature = "blocking")]
+pub use super::Client;
+pub use super::{Endpoint, IexError, Money, MyError, Response, Result, Strictness, SymbolLookup};
@@ -4,57 +4,115 @@ Returns various reference data.
 
  */
 
-use super::{from_bool_str, from_str, Result};
-use chrono::{DateTime, NaiveDate, Utc};
+#[cfg(feature = "blocking")]
+use super::concurrency::map_concurrent;
+#[cfg(feature = "blocking")]
+use super::Client;
+use super::{
+    check_body, check_status, from_bool_str, from_naive_date_str, from_naive_datetime_str,
+    from_str, market_hours, to_bool_str, to_naive_date_str, to_naive_datetime_str, to_str,
+    with_strictness, Response, Result, IEX_ENDPOINT,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_aux::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-pub struct ReferenceData;
+/// Most daily lists `ReferenceData::corporate_actions_range` fetches at
+/// once.
+const MAX_CONCURRENT_DAILY_LISTS: usize = 4;
 
-// TODO(markcol): need to deserialze from string form to enum value.
+/// How long a symbol list fetched by `ReferenceData::cached_symbols` is
+/// reused before being fetched again.
+const SYMBOLS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[cfg(feature = "blocking")]
+pub struct ReferenceData<'a> {
+    pub(crate) client: &'a Client,
+}
+
+/// Common issue type of a symbol, as reported by `/ref-data/symbols`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CommonIssueType {
+    /// ADR (`ad`).
+    #[serde(rename = "ad")]
     ADR,
+    /// REIT (`re`).
+    #[serde(rename = "re")]
     REIT,
+    /// Closed end fund (`ce`).
+    #[serde(rename = "ce")]
     ClosedEndFund,
+    /// Secondary issue (`si`).
+    #[serde(rename = "si")]
     SecondaryIssue,
+    /// Limited partnership (`lp`).
+    #[serde(rename = "lp")]
     LimitedPartnership,
+    /// Common stock (`cs`).
+    #[serde(rename = "cs")]
     CommonStock,
+    /// ETF (`et`).
+    #[serde(rename = "et")]
     ETF,
-    NA, // Not applicable
+    /// Not applicable, or a type this crate doesn't know about.
+    #[serde(rename = "N/A", other)]
+    NA,
 }
 
 pub type Symbols = Vec<SymbolData>;
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-#[derive(Serialize, Deserialize, Debug)]
 pub struct SymbolData {
     /// The symbol represented in Nasdaq Integrated symbology (INET).
-    symbol: String,
+    pub symbol: String,
     /// The name of the company or security.
-    name: String,
+    pub name: String,
     /// The date the symbol reference data was generated.
-    date: NaiveDate,
+    pub date: NaiveDate,
     /// True if the symbol is enabled for trading on IEX.
-    is_enabled: bool,
+    pub is_enabled: bool,
     /// The common issue type.
     #[serde(rename = "type")]
-    issue_type: String, // TODO(markcol): Convert to use CommonIssueType
+    pub issue_type: CommonIssueType,
     /// Unique ID applied by IEX to track securities through symbol changes.
-    #[serde(default, deserialize_with = "from_str")]
-    iex_id: u64,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub iex_id: u64,
+}
+
+code_enum! {
+    /// Event of a corporate actions record, as coded in the daily list.
+    pub enum IssueEvent {
+        /// New issue added (`AA`).
+        NewAdd => "AA",
+        /// Name change (`NA`).
+        NameChange => "NA",
+        /// Symbol change (`CH`).
+        SymbolChange => "CH",
+        /// Issue deleted (`DL`).
+        Delisting => "DL",
+    }
 }
 
 pub type CorporateActions = Vec<CorporateActionsData>;
 
-// TODO(markcol): convert record_update_time and daily_list_timestamp to use
-// Chrono datetime.
-#[serde(rename_all = "PascalCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
 pub struct CorporateActionsData {
     #[serde(rename = "RecordID")]
     record_id: String,
-    daily_list_timestamp: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    daily_list_timestamp: Option<NaiveDateTime>,
     effective_date: NaiveDate,
-    issue_event: String,
+    issue_event: IssueEvent,
     #[serde(rename = "CurrentSymbolinINETSymbology")]
     current_symbol_in_inets_symbology: String,
     #[serde(rename = "CurrentSymbolinCQSSymbology")]
@@ -72,44 +130,484 @@ pub struct CorporateActionsData {
     current_company_name: String,
     new_company_name: String,
     current_listing_center: String,
-    #[serde(default, deserialize_with = "from_str")]
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
     new_round_lot_size: u64,
     #[serde(
         rename = "CurrentLULDTierIndicator",
         default,
-        deserialize_with = "from_str"
+        deserialize_with = "from_str",
+        serialize_with = "to_str"
     )]
     current_luld_tier_indicator: u64,
     #[serde(
         rename = "NewLULDTierIndicator",
         default,
-        deserialize_with = "from_str"
+        deserialize_with = "from_str",
+        serialize_with = "to_str"
     )]
     new_luld_tier_indicator: u64,
-    #[serde(default, deserialize_with = "from_str")]
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
     expiration_date: u64,
-    #[serde(default, deserialize_with = "from_str")]
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
     separation_date: u64,
-    #[serde(default, deserialize_with = "from_str")]
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
     settlement_date: u64,
-    #[serde(default, deserialize_with = "from_str")]
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
     maturity_date: u64,
-    #[serde(default, deserialize_with = "from_str")]
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
     redemption_date: u64,
     current_financial_status: String,
     new_financial_status: String,
-    #[serde(deserialize_with = "from_bool_str")]
+    #[serde(deserialize_with = "from_bool_str", serialize_with = "to_bool_str")]
     when_issued_flag: bool,
-    #[serde(deserialize_with = "from_bool_str")]
+    #[serde(deserialize_with = "from_bool_str", serialize_with = "to_bool_str")]
     when_distributed_flag: bool,
-    #[serde(rename = "IPOFlag", deserialize_with = "from_bool_str")]
+    #[serde(
+        rename = "IPOFlag",
+        deserialize_with = "from_bool_str",
+        serialize_with = "to_bool_str"
+    )]
     ipo_flag: bool,
     #[serde(rename = "NotesforEachEntry")]
     notes_for_each_entry: String,
-    record_update_time: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    record_update_time: Option<NaiveDateTime>,
+    /// Only sent to tokens permissioned for CUSIP information.
+    #[serde(rename = "CUSIP", default, skip_serializing_if = "Option::is_none")]
+    cusip: Option<String>,
+}
+
+impl CorporateActionsData {
+    /// Returns the event of the record, e.g. to keep only delistings.
+    pub fn issue_event(&self) -> &IssueEvent {
+        &self.issue_event
+    }
+
+    /// Returns the current symbol of the security in INET symbology, the one
+    /// used by the IEX APIs, e.g. `ZEXIT-`. Records which only fill in the
+    /// CMS symbology are converted with `cms_to_inet_symbol`.
+    pub fn current_symbol(&self) -> String {
+        inet_symbol(
+            &self.current_symbol_in_inets_symbology,
+            &self.current_symbol_in_cmss_symbology,
+        )
+    }
+
+    /// Same as `current_symbol` for the new symbol of the security, `None`
+    /// if the action doesn't change it.
+    pub fn new_symbol(&self) -> Option<String> {
+        Some(inet_symbol(
+            &self.new_symbol_in_inets_symbology,
+            &self.new_symbol_in_cmss_symbology,
+        ))
+        .filter(|symbol| !symbol.is_empty())
+    }
+}
+
+/// Returns the INET symbol `inet`, or the conversion of the CMS symbol `cms`
+/// if it is blank.
+fn inet_symbol(inet: &str, cms: &str) -> String {
+    match inet.trim() {
+        "" => cms_to_inet_symbol(cms),
+        inet => inet.to_uppercase(),
+    }
+}
+
+/// Converts a symbol in CMS symbology, whose suffixes are separated by a
+/// space (`ZEXIT PR`, `ZEXIT PRA`, `ZEXIT WS`), into INET symbology
+/// (`ZEXIT-`, `ZEXIT-A`, `ZEXIT+`), so that symbols of different vendors
+/// compare equal. Unknown suffixes are kept as a class, e.g. `BRK A` becomes
+/// `BRK.A`.
+pub fn cms_to_inet_symbol(symbol: &str) -> String {
+    let mut parts = symbol.split_whitespace();
+    let root = parts.next().unwrap_or_default().to_uppercase();
+    let suffix = match parts.next() {
+        Some(suffix) => suffix.to_uppercase(),
+        None => return root,
+    };
+    let (kind, class) = ["PR", "WS", "RT", "WI", "CL", "U"]
+        .iter()
+        .find(|kind| suffix.starts_with(*kind))
+        .map_or(("", suffix.as_str()), |kind| (*kind, &suffix[kind.len()..]));
+    let marker = match kind {
+        "PR" => "-",
+        "WS" => "+",
+        "RT" => "^",
+        "WI" => "#",
+        "CL" => "*",
+        "U" => "=",
+        _ => ".",
+    };
+    format!("{}{}{}", root, marker, class)
+}
+
+pub type DailyDividends = Vec<DailyDividendData>;
+
+/// Record of the dividends daily list, announcing a dividend or a change to
+/// one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct DailyDividendData {
+    #[serde(rename = "RecordID")]
+    pub record_id: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    pub daily_list_timestamp: Option<NaiveDateTime>,
+    /// Whether the record is new or changes or deletes an earlier one, e.g.
+    /// `NEW`.
+    pub event_type: String,
+    #[serde(rename = "SymbolinINETSymbology")]
+    pub symbol_in_inet_symbology: String,
+    #[serde(rename = "SymbolinCQSSymbology")]
+    pub symbol_in_cqs_symbology: String,
+    #[serde(rename = "SymbolinCMSSymbology")]
+    pub symbol_in_cms_symbology: String,
+    pub security_name: String,
+    pub company_name: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_date_str",
+        serialize_with = "to_naive_date_str"
+    )]
+    pub declaration_date: Option<NaiveDate>,
+    pub amount_description: String,
+    pub payment_frequency: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_date_str",
+        serialize_with = "to_naive_date_str"
+    )]
+    pub ex_date: Option<NaiveDate>,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_date_str",
+        serialize_with = "to_naive_date_str"
+    )]
+    pub record_date: Option<NaiveDate>,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_date_str",
+        serialize_with = "to_naive_date_str"
+    )]
+    pub payment_date: Option<NaiveDate>,
+    #[serde(rename = "DividendTypeID")]
+    pub dividend_type_id: String,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub cash_amount: f64,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub gross_amount: f64,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub net_amount: f64,
+    #[serde(rename = "NotesforEachEntry")]
+    pub notes_for_each_entry: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    pub record_update_time: Option<NaiveDateTime>,
+}
+
+impl DailyDividendData {
+    /// Returns the symbol of the security in INET symbology, see
+    /// `CorporateActionsData::current_symbol`.
+    pub fn symbol(&self) -> String {
+        inet_symbol(
+            &self.symbol_in_inet_symbology,
+            &self.symbol_in_cms_symbology,
+        )
+    }
+}
+
+pub type NextDayExDates = Vec<NextDayExDateData>;
+
+/// Record of the next day ex-date daily list, for a security going ex
+/// dividend on the next trading day.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct NextDayExDateData {
+    #[serde(rename = "RecordID")]
+    pub record_id: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    pub daily_list_timestamp: Option<NaiveDateTime>,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_date_str",
+        serialize_with = "to_naive_date_str"
+    )]
+    pub ex_date: Option<NaiveDate>,
+    #[serde(rename = "SymbolinINETSymbology")]
+    pub symbol_in_inet_symbology: String,
+    #[serde(rename = "SymbolinCQSSymbology")]
+    pub symbol_in_cqs_symbology: String,
+    #[serde(rename = "SymbolinCMSSymbology")]
+    pub symbol_in_cms_symbology: String,
+    pub security_name: String,
+    pub company_name: String,
+    #[serde(rename = "DividendTypeID")]
+    pub dividend_type_id: String,
+    pub amount_description: String,
+    pub payment_frequency: String,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub cash_amount: f64,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub gross_amount: f64,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub net_amount: f64,
+    #[serde(rename = "NotesforEachEntry")]
+    pub notes_for_each_entry: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    pub record_update_time: Option<NaiveDateTime>,
+}
+
+impl NextDayExDateData {
+    /// Returns the symbol of the security in INET symbology, see
+    /// `CorporateActionsData::current_symbol`.
+    pub fn symbol(&self) -> String {
+        inet_symbol(
+            &self.symbol_in_inet_symbology,
+            &self.symbol_in_cms_symbology,
+        )
+    }
+}
+
+pub type SymbolDirectory = Vec<SymbolDirectoryData>;
+
+/// Record of the symbol directory daily list, describing a security listed
+/// on IEX.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct SymbolDirectoryData {
+    #[serde(rename = "RecordID")]
+    pub record_id: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    pub daily_list_timestamp: Option<NaiveDateTime>,
+    #[serde(rename = "SymbolinINETSymbology")]
+    pub symbol_in_inet_symbology: String,
+    #[serde(rename = "SymbolinCQSSymbology")]
+    pub symbol_in_cqs_symbology: String,
+    #[serde(rename = "SymbolinCMSSymbology")]
+    pub symbol_in_cms_symbology: String,
+    pub security_name: String,
+    pub company_name: String,
+    #[serde(deserialize_with = "from_bool_str", serialize_with = "to_bool_str")]
+    pub test_issue: bool,
+    pub issue_description: String,
+    pub issue_type: String,
+    pub issue_sub_type: String,
+    #[serde(rename = "SIC")]
+    pub sic: String,
+    pub transfer_agent: String,
+    pub financial_status: String,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub round_lot_size: u64,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub previous_official_closing_price: f64,
+    #[serde(default, deserialize_with = "from_str", serialize_with = "to_str")]
+    pub adjusted_previous_official_closing_price: f64,
+    #[serde(deserialize_with = "from_bool_str", serialize_with = "to_bool_str")]
+    pub when_issued_flag: bool,
+    #[serde(deserialize_with = "from_bool_str", serialize_with = "to_bool_str")]
+    pub when_distributed_flag: bool,
+    #[serde(
+        rename = "IPOFlag",
+        deserialize_with = "from_bool_str",
+        serialize_with = "to_bool_str"
+    )]
+    pub ipo_flag: bool,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_date_str",
+        serialize_with = "to_naive_date_str"
+    )]
+    pub first_date_listed: Option<NaiveDate>,
+    #[serde(
+        rename = "LULDTierIndicator",
+        default,
+        deserialize_with = "from_str",
+        serialize_with = "to_str"
+    )]
+    pub luld_tier_indicator: u64,
+    #[serde(rename = "CountryofIncorporation")]
+    pub country_of_incorporation: String,
+    #[serde(
+        default,
+        deserialize_with = "from_naive_datetime_str",
+        serialize_with = "to_naive_datetime_str"
+    )]
+    pub record_update_time: Option<NaiveDateTime>,
+}
+
+impl SymbolDirectoryData {
+    /// Returns the symbol of the security in INET symbology, see
+    /// `CorporateActionsData::current_symbol`.
+    pub fn symbol(&self) -> String {
+        inet_symbol(
+            &self.symbol_in_inet_symbology,
+            &self.symbol_in_cms_symbology,
+        )
+    }
+}
+
+/// Delimited formats of the daily list files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DailyListFormat {
+    /// Comma separated values (`format=csv`).
+    Csv,
+    /// Pipe separated values (`format=psv`).
+    Psv,
+}
+
+impl DailyListFormat {
+    /// Value of the `format` query parameter.
+    fn param(self) -> &'static str {
+        match self {
+            DailyListFormat::Csv => "csv",
+            DailyListFormat::Psv => "psv",
+        }
+    }
+
+    fn delimiter(self) -> u8 {
+        match self {
+            DailyListFormat::Csv => b',',
+            DailyListFormat::Psv => b'|',
+        }
+    }
+}
+
+/// Daily list published by IEX, see `ReferenceData::daily_list_sample`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DailyListKind {
+    /// Corporate actions, e.g. new listings and symbol changes, typed as
+    /// `CorporateActions`.
+    CorporateActions,
+    /// Dividend announcements, typed as `DailyDividends`.
+    Dividends,
+    /// Securities going ex dividend on the next trading day, typed as
+    /// `NextDayExDates`.
+    NextDayExDate,
+    /// Securities listed on IEX, typed as `SymbolDirectory`.
+    SymbolDirectory,
+}
+
+impl DailyListKind {
+    /// Path of the list under `/ref-data/daily-list`.
+    fn path(self) -> &'static str {
+        match self {
+            DailyListKind::CorporateActions => "corporate-actions",
+            DailyListKind::Dividends => "dividends",
+            DailyListKind::NextDayExDate => "next-day-ex-date",
+            DailyListKind::SymbolDirectory => "symbol-directory",
+        }
+    }
+}
+
+/// Differences between two symbol lists, keyed by symbol, see
+/// `diff_symbols`.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolDiff {
+    /// Symbols only in the new list, e.g. new listings.
+    pub added: BTreeMap<String, SymbolData>,
+    /// Symbols only in the old list, e.g. delistings.
+    pub removed: BTreeMap<String, SymbolData>,
+    /// Symbols in both lists whose name or enabled flag changed.
+    pub changed: BTreeMap<String, SymbolChange>,
+}
+
+/// Entry of a symbol in the old and the new list of a `SymbolDiff`.
+#[derive(Debug, Clone)]
+pub struct SymbolChange {
+    pub old: SymbolData,
+    pub new: SymbolData,
+}
+
+impl SymbolDiff {
+    /// Returns true if the lists have the same symbols, names and enabled
+    /// flags.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares two `/ref-data/symbols` lists, e.g. yesterday's and today's, to
+/// track listings, delistings, renames and symbols enabled or disabled for
+/// trading. Other fields, like `date`, are ignored.
+pub fn diff_symbols(old: &Symbols, new: &Symbols) -> SymbolDiff {
+    let old_by_symbol: HashMap<&str, &SymbolData> =
+        old.iter().map(|s| (s.symbol.as_str(), s)).collect();
+    let new_by_symbol: HashMap<&str, &SymbolData> =
+        new.iter().map(|s| (s.symbol.as_str(), s)).collect();
+
+    let mut diff = SymbolDiff::default();
+    for (&symbol, &new) in &new_by_symbol {
+        match old_by_symbol.get(symbol) {
+            None => {
+                diff.added.insert(symbol.to_string(), new.clone());
+            }
+            Some(&old) if old.name != new.name || old.is_enabled != new.is_enabled => {
+                let change = SymbolChange {
+                    old: old.clone(),
+                    new: new.clone(),
+                };
+                diff.changed.insert(symbol.to_string(), change);
+            }
+            Some(_) => {}
+        }
+    }
+    for (&symbol, &old) in &old_by_symbol {
+        if !new_by_symbol.contains_key(symbol) {
+            diff.removed.insert(symbol.to_string(), old.clone());
+        }
+    }
+    diff
+}
+
+/// Parses a symbol list in CSV format. The header row names the columns
+/// like the JSON keys, e.g. `isEnabled`.
+fn parse_symbols_csv<R: Read>(csv: R) -> Result<Symbols> {
+    let mut reader = csv::Reader::from_reader(csv);
+    let mut symbols = Symbols::new();
+    for record in reader.deserialize() {
+        symbols.push(record?);
+    }
+    Ok(symbols)
+}
+
+/// Parses a corporate actions daily list in `format`. Columns are matched by
+/// the header row, which uses the JSON keys in the order of the daily list
+/// specification, so the CUSIP column may or may not be present.
+fn parse_corporate_actions<R: Read>(data: R, format: DailyListFormat) -> Result<CorporateActions> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(format.delimiter())
+        .from_reader(data);
+    let mut actions = CorporateActions::new();
+    for record in reader.deserialize() {
+        actions.push(record?);
+    }
+    Ok(actions)
 }
 
-impl ReferenceData {
+#[cfg(feature = "blocking")]
+impl<'a> ReferenceData<'a> {
     /// Returns an array of symbols IEX supports for trading. This list is
     /// updated daily as of 7:45 a.m. ET. Symbols may be added or removed by
     /// IEX after the list was produced.
@@ -141,9 +639,108 @@ impl ReferenceData {
     /// | | CS - Common Stock |
     /// | | ET - ETF |
     /// | iexId | unique ID applied by IEX to track securities through symbol changes. |
-
     pub fn symbols(&self) -> Result<Symbols> {
-        Ok(Vec::new())
+        self.client
+            .get(&format!("{}/ref-data/symbols", IEX_ENDPOINT))?
+            .try_into_with(self.client.strictness())
+    }
+
+    /// Same as `symbols`, but requests the list as CSV, which is smaller
+    /// than the JSON for the full universe and is parsed straight into
+    /// `SymbolData` without building an intermediate JSON document.
+    pub fn symbols_csv(&self) -> Result<Symbols> {
+        let raw = self
+            .client
+            .get_raw(&format!("{}/ref-data/symbols?format=csv", IEX_ENDPOINT))?;
+        with_strictness(self.client.strictness(), || {
+            parse_symbols_csv(raw.body.as_bytes())
+        })
+    }
+
+    /// Same as `symbols`, but reuses the list fetched by an earlier call if
+    /// it is less than an hour old.
+    pub fn cached_symbols(&self) -> Result<Arc<Symbols>> {
+        let mut cache = self.client.inner.symbols.lock().unwrap();
+        if let Some((fetched, ref symbols)) = *cache {
+            if fetched.elapsed() < SYMBOLS_CACHE_TTL {
+                return Ok(symbols.clone());
+            }
+        }
+        let symbols = Arc::new(self.symbols()?);
+        *cache = Some((Instant::now(), symbols.clone()));
+        Ok(symbols)
+    }
+
+    /// Fetches the symbol list every `interval` and calls `on_change` with
+    /// the differences whenever it changed since the previous fetch, or with
+    /// the error of a fetch which failed even after the client's retries. The
+    /// first list compared is the cached one, see `cached_symbols`, and every
+    /// fetched list replaces it in the cache.
+    ///
+    /// Blocks the calling thread until `on_change` returns `false`. A failed
+    /// fetch doesn't end the watch unless `on_change` says so: the next fetch
+    /// is compared against the last list fetched successfully. Only fails if
+    /// the initial list can't be fetched.
+    pub fn watch_symbols<F>(&self, interval: Duration, mut on_change: F) -> Result<()>
+    where
+        F: FnMut(Result<SymbolDiff>) -> bool,
+    {
+        let mut previous = self.cached_symbols()?;
+        loop {
+            std::thread::sleep(interval);
+            let symbols = match self.symbols() {
+                Ok(symbols) => Arc::new(symbols),
+                Err(err) => {
+                    if !on_change(Err(err)) {
+                        return Ok(());
+                    }
+                    continue;
+                }
+            };
+            *self.client.inner.symbols.lock().unwrap() = Some((Instant::now(), symbols.clone()));
+            let diff = diff_symbols(&previous, &symbols);
+            previous = symbols;
+            if !diff.is_empty() && !on_change(Ok(diff)) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Returns the symbols whose company or security name contains `query`,
+    /// ignoring case, e.g. `"apple"` finds `AAPL`.
+    ///
+    /// IEX has no name search endpoint, so the (cached) symbol list is
+    /// searched locally. Names starting with `query` rank before names
+    /// containing it elsewhere, then shorter names rank first.
+    pub fn search(&self, query: &str) -> Result<Vec<SymbolData>> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(usize, SymbolData)> = self
+            .cached_symbols()?
+            .iter()
+            .filter_map(|s| {
+                s.name
+                    .to_lowercase()
+                    .find(&query)
+                    .map(|pos| (pos, s.clone()))
+            })
+            .collect();
+        matches.sort_by(|(a_pos, a), (b_pos, b)| {
+            (*a_pos > 0, a.name.len(), &a.name).cmp(&(*b_pos > 0, b.name.len(), &b.name))
+        });
+        Ok(matches.into_iter().map(|(_, s)| s).collect())
+    }
+
+    /// Returns the symbols of the given common issue type, e.g. only ETFs.
+    ///
+    /// IEX can't filter by type, so the (cached) full list is filtered
+    /// locally.
+    pub fn symbols_of_type(&self, issue_type: CommonIssueType) -> Result<Symbols> {
+        Ok(self
+            .cached_symbols()?
+            .iter()
+            .filter(|s| s.issue_type == issue_type)
+            .cloned()
+            .collect())
     }
 
     /// corporate_actions returns an array of new issues, symbol and name
@@ -233,22 +830,101 @@ impl ReferenceData {
         Ok(Vec::new())
     }
 
-    pub fn dividends(&self) -> Result<()> {
-        Ok(())
+    /// Same as `corporate_actions`, but requests the daily list as CSV or
+    /// PSV, the canonical formats of the dataset. `date` is a `YYYYMMDD`
+    /// date or `sample`; the latest list is returned when `None`.
+    pub fn corporate_actions_csv(
+        &self,
+        date: Option<&str>,
+        format: DailyListFormat,
+    ) -> Result<CorporateActions> {
+        let mut url = format!("{}/ref-data/daily-list/corporate-actions", IEX_ENDPOINT);
+        if let Some(date) = date {
+            url = format!("{}/{}", url, date);
+        }
+        let raw = self
+            .client
+            .get_raw(&format!("{}?format={}", url, format.param()))?;
+        check_status(&raw)?;
+        check_body(&raw)?;
+        with_strictness(self.client.strictness(), || {
+            parse_corporate_actions(raw.body.as_bytes(), format)
+        })
+    }
+
+    /// Returns the corporate actions daily lists of the trading days from
+    /// `start` to `end` included, keyed by date, e.g. to backfill history.
+    /// Weekends and market holidays are skipped, see `market_hours`.
+    ///
+    /// The lists are fetched concurrently, a few at a time, through the
+    /// client, so its retry and scheduling settings apply. Fails if any list
+    /// can't be fetched.
+    pub fn corporate_actions_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<HashMap<NaiveDate, CorporateActions>> {
+        let days: Vec<NaiveDate> = start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .filter(|day| market_hours::is_trading_day(*day))
+            .collect();
+        let results = map_concurrent(&days, MAX_CONCURRENT_DAILY_LISTS, |day| {
+            let date = day.format("%Y%m%d").to_string();
+            self.corporate_actions_csv(Some(&date), DailyListFormat::Psv)
+        });
+        days.into_iter()
+            .zip(results)
+            .map(|(day, result)| Ok((day, result?)))
+            .collect()
+    }
+
+    /// Returns the sample IEX publishes of the daily list `kind`, e.g. to
+    /// explore its fields. Each list has a typed counterpart, see
+    /// `DailyListKind`.
+    pub fn daily_list_sample(&self, kind: DailyListKind) -> Result<Response> {
+        self.client.get(&format!(
+            "{}/ref-data/daily-list/{}/sample",
+            IEX_ENDPOINT,
+            kind.path()
+        ))
     }
 
-    pub fn next_day_ex_date(&self) -> Result<()> {
-        Ok(())
+    /// Returns the latest dividends daily list.
+    pub fn dividends(&self) -> Result<DailyDividends> {
+        self.daily_list(DailyListKind::Dividends)?
+            .try_into_with(self.client.strictness())
     }
 
-    pub fn listed_symbol_directory(&self) -> Result<()> {
-        Ok(())
+    /// Returns the latest next day ex-date daily list.
+    pub fn next_day_ex_date(&self) -> Result<NextDayExDates> {
+        self.daily_list(DailyListKind::NextDayExDate)?
+            .try_into_with(self.client.strictness())
+    }
+
+    /// Returns the latest symbol directory daily list.
+    pub fn listed_symbol_directory(&self) -> Result<SymbolDirectory> {
+        self.daily_list(DailyListKind::SymbolDirectory)?
+            .try_into_with(self.client.strictness())
+    }
+
+    /// Returns the latest daily list `kind` as JSON.
+    fn daily_list(&self, kind: DailyListKind) -> Result<Response> {
+        self.client.get(&format!(
+            "{}/ref-data/daily-list/{}",
+            IEX_ENDPOINT,
+            kind.path()
+        ))
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
     use super::*;
+    use crate::fixtures::{
+        CORPORATE_ACTION, CORPORATE_ACTIONS_PSV, DAILY_DIVIDEND, NEXT_DAY_EX_DATE, SYMBOL_DIRECTORY,
+    };
+    use crate::{IexError, MockTransport};
 
     #[test]
     fn symbol_date_deserialization() {
@@ -261,9 +937,302 @@ mod tests {
             "iexId":"2"
         }"#;
 
-        let s: SymbolData = serde_json::from_str(&json_data).unwrap();
+        let s: SymbolData = serde_json::from_str(json_data).unwrap();
         assert_eq!(s.date, NaiveDate::from_ymd(2018, 10, 23));
         assert_eq!(s.iex_id, 2);
+        assert_eq!(s.issue_type, CommonIssueType::CommonStock);
+    }
+
+    #[test]
+    fn symbol_unknown_type_deserialization() {
+        let json_data = r#"{
+            "symbol":"BTCUSDT",
+            "name":"Bitcoin USD",
+            "date":"2018-10-23",
+            "isEnabled":true,
+            "type":"crypto",
+            "iexId":"10000000"
+        }"#;
+
+        let s: SymbolData = serde_json::from_str(json_data).unwrap();
+        assert_eq!(s.issue_type, CommonIssueType::NA);
+    }
+
+    #[test]
+    fn symbols_csv() {
+        let url = format!("{}/ref-data/symbols?format=csv", IEX_ENDPOINT);
+        let csv_data = "symbol,name,date,isEnabled,type,iexId
+A,Agilent Technologies Inc.,2018-10-23,true,cs,2
+AAPL,Apple Inc.,2018-10-23,true,cs,11
+SPY,\"SPDR S&P 500 ETF Trust, Series 1\",2018-10-23,false,et,7
+BTCUSDT,Bitcoin USD,2018-10-23,true,crypto,10000000
+";
+        let client = Client::with_transport(MockTransport::new().with(&url, csv_data));
+
+        let symbols = client.reference_data().symbols_csv().unwrap();
+        assert_eq!(symbols.len(), 4);
+        assert_eq!(symbols[1].symbol, "AAPL");
+        assert_eq!(symbols[1].iex_id, 11);
+        assert_eq!(symbols[2].name, "SPDR S&P 500 ETF Trust, Series 1");
+        assert!(!symbols[2].is_enabled);
+        assert_eq!(symbols[2].issue_type, CommonIssueType::ETF);
+        assert_eq!(symbols[3].issue_type, CommonIssueType::NA);
+    }
+
+    #[test]
+    fn corporate_actions_csv_errors() {
+        let url = format!(
+            "{}/ref-data/daily-list/corporate-actions/20171108?format=psv",
+            IEX_ENDPOINT
+        );
+        let fetch = |transport| {
+            Client::with_transport(transport)
+                .reference_data()
+                .corporate_actions_csv(Some("20171108"), DailyListFormat::Psv)
+                .unwrap_err()
+                .downcast::<IexError>()
+        };
+
+        match fetch(MockTransport::new().with_status(&url, 503, "Service Unavailable")) {
+            Ok(IexError::Http {
+                status: Some(503), ..
+            }) => {}
+            err => panic!("expected IexError::Http, got {:?}", err),
+        }
+        let html = "<html><head><title>Maintenance</title></head></html>";
+        match fetch(MockTransport::new().with(&url, html)) {
+            Ok(IexError::ServiceUnavailable { .. }) => {}
+            err => panic!("expected IexError::ServiceUnavailable, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn daily_list_samples() {
+        let url = |path| format!("{}/ref-data/daily-list/{}/sample", IEX_ENDPOINT, path);
+        let transport = MockTransport::new()
+            .with(
+                &url("corporate-actions"),
+                &format!("[{}]", CORPORATE_ACTION),
+            )
+            .with(&url("dividends"), &format!("[{}]", DAILY_DIVIDEND))
+            .with(&url("next-day-ex-date"), &format!("[{}]", NEXT_DAY_EX_DATE))
+            .with(&url("symbol-directory"), &format!("[{}]", SYMBOL_DIRECTORY));
+        let client = Client::with_transport(transport);
+        let reference = client.reference_data();
+        let sample = |kind| reference.daily_list_sample(kind).unwrap();
+
+        let actions: CorporateActions = sample(DailyListKind::CorporateActions).into_vec().unwrap();
+        assert_eq!(actions[0].record_id, " CA20171108153808144");
+
+        let dividends: DailyDividends = sample(DailyListKind::Dividends).into_vec().unwrap();
+        assert_eq!(dividends[0].symbol(), "ZEXIT");
+        assert_eq!(dividends[0].ex_date, NaiveDate::from_ymd_opt(2017, 11, 9));
+        assert_eq!(dividends[0].cash_amount, 0.09);
+        assert_eq!(dividends[0].net_amount, 0.0);
+
+        let ex_dates: NextDayExDates = sample(DailyListKind::NextDayExDate).into_vec().unwrap();
+        assert_eq!(ex_dates[0].record_id, " NX20171108154436477");
+        assert_eq!(ex_dates[0].dividend_type_id, "XS");
+
+        let directory: SymbolDirectory = sample(DailyListKind::SymbolDirectory).into_vec().unwrap();
+        assert_eq!(directory[0].symbol(), "ZEXIT-");
+        assert!(directory[0].test_issue);
+        assert_eq!(directory[0].round_lot_size, 100);
+        assert_eq!(
+            directory[0].first_date_listed,
+            NaiveDate::from_ymd_opt(2017, 9, 15)
+        );
+    }
+
+    #[test]
+    fn latest_daily_lists() {
+        let url = |path| format!("{}/ref-data/daily-list/{}", IEX_ENDPOINT, path);
+        let transport = MockTransport::new()
+            .with(&url("dividends"), &format!("[{}]", DAILY_DIVIDEND))
+            .with(&url("next-day-ex-date"), &format!("[{}]", NEXT_DAY_EX_DATE))
+            .with(&url("symbol-directory"), &format!("[{}]", SYMBOL_DIRECTORY));
+        let client = Client::with_transport(transport);
+        let reference = client.reference_data();
+
+        assert_eq!(reference.dividends().unwrap().len(), 1);
+        assert_eq!(reference.next_day_ex_date().unwrap().len(), 1);
+        let directory = reference.listed_symbol_directory().unwrap();
+        assert_eq!(directory[0].company_name, "ZEXIT Test Company");
+    }
+
+    #[test]
+    fn daily_list_dates_round_trip() {
+        let mut json: serde_json::Value = serde_json::from_str(DAILY_DIVIDEND).unwrap();
+        json["RecordDate"] = "".into();
+        let dividend: DailyDividendData = serde_json::from_value(json).unwrap();
+        assert_eq!(dividend.record_date, None);
+
+        let json = serde_json::to_value(&dividend).unwrap();
+        assert_eq!(json["ExDate"], "2017-11-09");
+        assert_eq!(json["RecordDate"], "");
+        let back: DailyDividendData = serde_json::from_value(json).unwrap();
+        assert_eq!(back.payment_date, dividend.payment_date);
+        assert_eq!(back.record_date, None);
+    }
+
+    #[test]
+    fn corporate_actions_psv() {
+        let url = format!(
+            "{}/ref-data/daily-list/corporate-actions/sample?format=psv",
+            IEX_ENDPOINT
+        );
+        let client = Client::with_transport(MockTransport::new().with(&url, CORPORATE_ACTIONS_PSV));
+
+        let actions = client
+            .reference_data()
+            .corporate_actions_csv(Some("sample"), DailyListFormat::Psv)
+            .unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].record_id, " CA20171108153808144");
+        assert_eq!(actions[0].current_security_name, "ZEXIT Preffered Stock");
+        assert_eq!(actions[0].cusip, None);
+        assert_eq!(
+            actions[1].effective_date,
+            NaiveDate::from_ymd_opt(2017, 11, 13).unwrap()
+        );
+        assert_eq!(actions[1].new_round_lot_size, 100);
+        assert!(actions[1].ipo_flag);
+        assert_eq!(
+            actions[1].notes_for_each_entry,
+            "IPO, with a | in the notes"
+        );
+    }
+
+    #[test]
+    fn corporate_actions_range() {
+        // Thanksgiving week 2018: the 22nd is a holiday, then a weekend.
+        let url = |date: &str| {
+            format!(
+                "{}/ref-data/daily-list/corporate-actions/{}?format=psv",
+                IEX_ENDPOINT, date
+            )
+        };
+        let one_action: String = CORPORATE_ACTIONS_PSV
+            .lines()
+            .take(2)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let transport = Arc::new(
+            MockTransport::new()
+                .with(&url("20181121"), CORPORATE_ACTIONS_PSV)
+                .with(&url("20181123"), &one_action)
+                .with(&url("20181126"), CORPORATE_ACTIONS_PSV),
+        );
+        let client = Client::with_transport(transport.clone());
+
+        let day = |d| NaiveDate::from_ymd_opt(2018, 11, d).unwrap();
+        let lists = client
+            .reference_data()
+            .corporate_actions_range(day(21), day(26))
+            .unwrap();
+        assert_eq!(lists.len(), 3);
+        assert_eq!(lists[&day(21)].len(), 2);
+        assert_eq!(lists[&day(23)].len(), 1);
+        assert_eq!(lists[&day(26)].len(), 2);
+        assert_eq!(transport.requests().len(), 3);
+
+        let err = client
+            .reference_data()
+            .corporate_actions_range(day(26), day(27));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn issue_event() {
+        let events: Vec<IssueEvent> =
+            serde_json::from_str(r#"["AA", "NA", "CH", "DL", "ZZ"]"#).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                IssueEvent::NewAdd,
+                IssueEvent::NameChange,
+                IssueEvent::SymbolChange,
+                IssueEvent::Delisting,
+                IssueEvent::Other("ZZ".to_string()),
+            ]
+        );
+        assert_eq!(serde_json::to_string(&events[3]).unwrap(), r#""DL""#);
+
+        let ca: CorporateActionsData = serde_json::from_str(CORPORATE_ACTION).unwrap();
+        assert_eq!(ca.issue_event(), &IssueEvent::NewAdd);
+    }
+
+    #[test]
+    fn corporate_actions_csv_with_cusip() {
+        let csv_data = CORPORATE_ACTIONS_PSV
+            .lines()
+            .take(2)
+            .enumerate()
+            .map(|(i, line)| {
+                let cusip = if i == 0 { "CUSIP" } else { "123456789" };
+                format!("{},{}\n", line.replace('|', ","), cusip)
+            })
+            .collect::<String>();
+
+        let actions = parse_corporate_actions(csv_data.as_bytes(), DailyListFormat::Csv).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].cusip, Some("123456789".to_string()));
+    }
+
+    #[test]
+    fn search() {
+        let url = format!("{}/ref-data/symbols", IEX_ENDPOINT);
+        let json_data = r#"[
+            {"symbol":"A","name":"Agilent Technologies Inc.","date":"2018-10-23","isEnabled":true,"type":"cs","iexId":"2"},
+            {"symbol":"APLE","name":"Apple Hospitality REIT Inc.","date":"2018-10-23","isEnabled":true,"type":"re","iexId":"3"},
+            {"symbol":"AAPL","name":"Apple Inc.","date":"2018-10-23","isEnabled":true,"type":"cs","iexId":"11"},
+            {"symbol":"PINE","name":"Pineapple Holdings","date":"2018-10-23","isEnabled":true,"type":"cs","iexId":"12"}
+        ]"#;
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let found = client.reference_data().search("apple").unwrap();
+        let symbols: Vec<&str> = found.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["AAPL", "APLE", "PINE"]);
+    }
+
+    #[test]
+    fn symbols_of_type() {
+        let url = format!("{}/ref-data/symbols", IEX_ENDPOINT);
+        let json_data = r#"[
+            {"symbol":"A","name":"Agilent Technologies Inc.","date":"2018-10-23","isEnabled":true,"type":"cs","iexId":"2"},
+            {"symbol":"SPY","name":"SPDR S&P 500 ETF Trust","date":"2018-10-23","isEnabled":true,"type":"et","iexId":"7"},
+            {"symbol":"QQQ","name":"Invesco QQQ Trust","date":"2018-10-23","isEnabled":true,"type":"et","iexId":"8"},
+            {"symbol":"BABA","name":"Alibaba Group Holding Ltd.","date":"2018-10-23","isEnabled":true,"type":"ad","iexId":"9"}
+        ]"#;
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let etfs = client
+            .reference_data()
+            .symbols_of_type(CommonIssueType::ETF)
+            .unwrap();
+        let symbols: Vec<&str> = etfs.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["SPY", "QQQ"]);
+    }
+
+    #[test]
+    fn symbol_round_trip() {
+        let json_data = r#"{
+            "symbol":"A",
+            "name":"Agilent Technologies Inc.",
+            "date":"2018-10-23",
+            "isEnabled":true,
+            "type":"cs",
+            "iexId":"2"
+        }"#;
+
+        let s: SymbolData = serde_json::from_str(json_data).unwrap();
+        let value = serde_json::to_value(&s).unwrap();
+        assert_eq!(
+            value,
+            serde_json::from_str::<serde_json::Value>(json_data).unwrap()
+        );
+        let s: SymbolData = serde_json::from_value(value).unwrap();
+        assert_eq!(s.iex_id, 2);
     }
 
     #[test]
@@ -277,50 +1246,148 @@ mod tests {
             "iexId":""
         }"#;
 
-        let s: SymbolData = serde_json::from_str(&json_data).unwrap();
+        let s: SymbolData = serde_json::from_str(json_data).unwrap();
         assert_eq!(s.iex_id, 0);
     }
 
     #[test]
     fn corporate_actions_deserialization() {
-        let json_data = r#"{
-            "RecordID": " CA20171108153808144",
-            "DailyListTimestamp": "2017-11-08T17:00:00",
-            "EffectiveDate": "2017-11-10",
-            "IssueEvent": "AA",
-            "CurrentSymbolinINETSymbology": "ZEXIT-",
-            "CurrentSymbolinCQSSymbology": "ZEXITp",
-            "CurrentSymbolinCMSSymbology": "ZEXIT PR",
-            "NewSymbolinINETSymbology": "",
-            "NewSymbolinCQSSymbology": "",
-            "NewSymbolinCMSSymbology": "",
-            "CurrentSecurityName": "ZEXIT Preffered Stock",
-            "NewSecurityName": "",
-            "CurrentCompanyName": "ZEXIT Test Company",
-            "NewCompanyName": "",
-            "CurrentListingCenter": "",
-            "NewListingCenter": "V",
-            "DelistingReason": "",
-            "CurrentRoundLotSize": "100",
-            "NewRoundLotSize": "",
-            "CurrentLULDTierIndicator": "0",
-            "NewLULDTierIndicator": "",
-            "ExpirationDate": "0",
-            "SeparationDate": "0",
-            "SettlementDate": "0",
-            "MaturityDate": "0",
-            "RedemptionDate": "0",
-            "CurrentFinancialStatus": "0",
-            "NewFinancialStatus": "",
-            "WhenIssuedFlag": "N",
-            "WhenDistributedFlag": "N",
-            "IPOFlag": "N",
-            "NotesforEachEntry": "New preferred ZIEXT security",
-            "RecordUpdateTime": "2017-11-08T16:34:43"
-        }"#;
+        let json_data = CORPORATE_ACTION;
 
-        let ca: CorporateActionsData = serde_json::from_str(&json_data).unwrap();
-        assert_eq!(ca.ipo_flag, false);
+        let ca: CorporateActionsData = serde_json::from_str(json_data).unwrap();
+        assert!(!ca.ipo_flag);
         assert_eq!(ca.effective_date, NaiveDate::from_ymd(2017, 11, 10));
+        assert_eq!(
+            ca.daily_list_timestamp,
+            NaiveDate::from_ymd_opt(2017, 11, 8).and_then(|d| d.and_hms_opt(17, 0, 0))
+        );
+        assert_eq!(
+            ca.record_update_time,
+            NaiveDate::from_ymd_opt(2017, 11, 8).and_then(|d| d.and_hms_opt(16, 34, 43))
+        );
+    }
+
+    #[test]
+    fn corporate_actions_symbols() {
+        let ca: CorporateActionsData = serde_json::from_str(CORPORATE_ACTION).unwrap();
+        assert_eq!(ca.current_symbol_in_inets_symbology, "ZEXIT-");
+        assert_eq!(ca.current_symbol_in_cqss_symbology, "ZEXITp");
+        assert_eq!(ca.current_symbol_in_cmss_symbology, "ZEXIT PR");
+        assert_eq!(ca.current_symbol(), "ZEXIT-");
+        assert_eq!(
+            cms_to_inet_symbol(&ca.current_symbol_in_cmss_symbology),
+            ca.current_symbol()
+        );
+        assert_eq!(ca.new_symbol(), None);
+
+        let mut json_data: serde_json::Value = serde_json::from_str(CORPORATE_ACTION).unwrap();
+        json_data["CurrentSymbolinINETSymbology"] = "".into();
+        json_data["NewSymbolinCMSSymbology"] = "zexit prb".into();
+        let ca: CorporateActionsData = serde_json::from_value(json_data).unwrap();
+        assert_eq!(ca.current_symbol(), "ZEXIT-");
+        assert_eq!(ca.new_symbol().as_deref(), Some("ZEXIT-B"));
+    }
+
+    #[test]
+    fn cms_symbols() {
+        assert_eq!(cms_to_inet_symbol("AAPL"), "AAPL");
+        assert_eq!(cms_to_inet_symbol("ZEXIT WS"), "ZEXIT+");
+        assert_eq!(cms_to_inet_symbol("ZEXIT WSA"), "ZEXIT+A");
+        assert_eq!(cms_to_inet_symbol("ZEXIT U"), "ZEXIT=");
+        assert_eq!(cms_to_inet_symbol("ZEXIT RT"), "ZEXIT^");
+        assert_eq!(cms_to_inet_symbol("BRK A"), "BRK.A");
+        assert_eq!(cms_to_inet_symbol(""), "");
+    }
+
+    #[test]
+    fn corporate_actions_round_trip() {
+        let ca: CorporateActionsData = serde_json::from_str(CORPORATE_ACTION).unwrap();
+        let json_data = serde_json::to_string(&ca).unwrap();
+        let ca2: CorporateActionsData = serde_json::from_str(&json_data).unwrap();
+        assert_eq!(serde_json::to_string(&ca2).unwrap(), json_data);
+        assert_eq!(ca2.record_update_time, ca.record_update_time);
+        assert_eq!(ca2.when_issued_flag, ca.when_issued_flag);
+    }
+
+    #[test]
+    fn corporate_actions_empty_timestamp_deserialization() {
+        let mut json_data: serde_json::Value = serde_json::from_str(CORPORATE_ACTION).unwrap();
+        json_data["DailyListTimestamp"] = "".into();
+
+        let ca: CorporateActionsData = serde_json::from_value(json_data).unwrap();
+        assert_eq!(ca.daily_list_timestamp, None);
+    }
+
+    #[test]
+    fn diff_symbol_lists() {
+        let symbol = |symbol: &str, name: &str, is_enabled| SymbolData {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            date: NaiveDate::from_ymd_opt(2018, 10, 23).unwrap(),
+            is_enabled,
+            issue_type: CommonIssueType::CommonStock,
+            iex_id: 0,
+        };
+        let old = vec![
+            symbol("A", "Agilent Technologies Inc.", true),
+            symbol("FB", "Facebook Inc.", true),
+            symbol("XYZ", "Delisted Corp.", true),
+            symbol("ZNGA", "Zynga Inc.", true),
+        ];
+        let mut new = vec![
+            symbol("A", "Agilent Technologies Inc.", true),
+            symbol("FB", "Meta Platforms Inc.", true),
+            symbol("NEW", "New Listing Inc.", true),
+            symbol("ZNGA", "Zynga Inc.", false),
+        ];
+        new[0].date = NaiveDate::from_ymd_opt(2018, 10, 24).unwrap();
+
+        let diff = diff_symbols(&old, &new);
+        assert_eq!(diff.added.keys().collect::<Vec<_>>(), ["NEW"]);
+        assert_eq!(diff.removed.keys().collect::<Vec<_>>(), ["XYZ"]);
+        assert_eq!(diff.changed.keys().collect::<Vec<_>>(), ["FB", "ZNGA"]);
+        assert_eq!(diff.changed["FB"].new.name, "Meta Platforms Inc.");
+        assert!(!diff.changed["ZNGA"].new.is_enabled);
+        assert!(diff_symbols(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn watch_symbols() {
+        let url = format!("{}/ref-data/symbols", IEX_ENDPOINT);
+        let symbol = |symbol: &str| {
+            format!(
+                r#"{{"symbol":"{}","name":"","date":"2018-10-23","isEnabled":true,
+                    "type":"cs","iexId":"1"}}"#,
+                symbol
+            )
+        };
+        let first = format!("[{}]", symbol("A"));
+        let second = format!("[{},{}]", symbol("A"), symbol("NEW"));
+        let transport = MockTransport::new()
+            .with_sequence(&url, &[&first, &first, "not json", &second, &second]);
+        let client = Client::with_transport(transport);
+
+        let mut errors = 0;
+        let mut diffs = Vec::new();
+        let result = client
+            .reference_data()
+            .watch_symbols(Duration::from_millis(1), |diff| match diff {
+                Ok(diff) => {
+                    diffs.push(diff);
+                    false
+                }
+                Err(_) => {
+                    errors += 1;
+                    true
+                }
+            });
+
+        assert!(result.is_ok());
+        assert_eq!(errors, 1);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].added.keys().collect::<Vec<_>>(), ["NEW"]);
+        assert!(diffs[0].removed.is_empty() && diffs[0].changed.is_empty());
+        let cached = client.reference_data().cached_symbols().unwrap();
+        assert_eq!(cached.len(), 2);
     }
 }
@@ -0,0 +1,418 @@
+/*! Retries
+
+Requests failing with a transient error (connection failure, rate limiting,
+maintenance page, `5xx` status) can be retried with exponential backoff:
+
+```no_run
+# #[cfg(feature = "blocking")]
+# fn main() {
+use iex::prelude::*;
+use std::time::Duration;
+
+let client = ClientBuilder::new()
+    .retry(RetryConfig {
+        max_retries: 5,
+        jitter: JitterStrategy::Equal,
+        ..Default::default()
+    })
+    .build();
+# }
+# #[cfg(not(feature = "blocking"))]
+# fn main() {}
+```
+
+A `RetryBudget` caps the retries of all requests of a client, so that a
+large batch fails fast during an outage instead of multiplying its requests:
+
+```no_run
+# #[cfg(feature = "blocking")]
+# fn main() {
+use iex::prelude::*;
+use std::time::Duration;
+
+let client = ClientBuilder::new()
+    .retry(RetryConfig::default())
+    .build()
+    .with_retry_budget(RetryBudget::new(20, Duration::from_secs(60)));
+# }
+# #[cfg(not(feature = "blocking"))]
+# fn main() {}
+```
+
+With the `tracing` feature, every retry emits a `WARN` event with the
+`attempt` number (counting from one), the `url`, the `delay_ms` before the
+retry and the `error` that triggered it.
+ */
+
+use super::{IexError, RawResponse, Result};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Randomization applied to the exponential backoff delays, as defined in
+/// "Exponential Backoff And Jitter" of the AWS Architecture Blog. Spreading
+/// the retries avoids many clients retrying in lockstep after an outage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JitterStrategy {
+    /// `min(max_delay, base_delay * 2^attempt)`, without randomization.
+    None,
+    /// Random between zero and the exponential delay.
+    #[default]
+    Full,
+    /// Half the exponential delay, plus a random delay up to the other half.
+    Equal,
+    /// Random between `base_delay` and three times the previous delay,
+    /// capped at `max_delay`.
+    Decorrelated,
+}
+
+/// Retry settings of a `Client`, see `ClientBuilder::retry`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay the backoff starts from.
+    pub base_delay: Duration,
+    /// Upper bound of a single delay.
+    pub max_delay: Duration,
+    pub jitter: JitterStrategy,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: JitterStrategy::default(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns the delay before retry number `attempt`, counting from zero.
+    /// `previous` is the delay before the previous retry, if any, which the
+    /// decorrelated strategy builds on.
+    pub fn delay(&self, attempt: u32, previous: Option<Duration>) -> Duration {
+        let exponential = 2u32
+            .checked_pow(attempt)
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        match self.jitter {
+            JitterStrategy::None => exponential,
+            JitterStrategy::Full => random_between(Duration::from_secs(0), exponential),
+            JitterStrategy::Equal => {
+                let half = exponential / 2;
+                half + random_between(Duration::from_secs(0), exponential - half)
+            }
+            JitterStrategy::Decorrelated => {
+                let previous = previous.unwrap_or(self.base_delay);
+                let high = previous.checked_mul(3).unwrap_or(self.max_delay);
+                random_between(self.base_delay, high).min(self.max_delay)
+            }
+        }
+    }
+}
+
+/// Token bucket of retries shared by all the requests of a client, see
+/// `Client::with_retry_budget`. It holds up to `max_retries` retries and
+/// refills at `max_retries` per `window`. Once it is empty, failed requests
+/// aren't retried until it refills.
+#[derive(Debug)]
+pub struct RetryBudget {
+    max_retries: u32,
+    window: Duration,
+    /// Retries left, and when they were last refilled.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RetryBudget {
+    /// Returns a full budget of `max_retries` retries per `window`.
+    pub fn new(max_retries: u32, window: Duration) -> Self {
+        RetryBudget {
+            max_retries,
+            window,
+            state: Mutex::new((f64::from(max_retries), Instant::now())),
+        }
+    }
+
+    /// Takes a retry from the budget, returning false if it is empty.
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let (ref mut tokens, ref mut refilled) = *state;
+        let now = Instant::now();
+        if self.window > Duration::from_secs(0) {
+            let elapsed = now.duration_since(*refilled).as_secs_f64();
+            let refill = elapsed / self.window.as_secs_f64() * f64::from(self.max_retries);
+            *tokens = (*tokens + refill).min(f64::from(self.max_retries));
+        }
+        *refilled = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Returns a random duration in `low..=high`.
+fn random_between(low: Duration, high: Duration) -> Duration {
+    if high <= low {
+        return low;
+    }
+    let span = (high - low).as_nanos() as u64;
+    let random = RandomState::new().build_hasher().finish();
+    low + Duration::from_nanos(random % span.saturating_add(1))
+}
+
+/// Emits the `tracing` event of retry number `attempt` of a request for
+/// `url` which failed with `result`, see the module documentation.
+#[cfg(feature = "tracing")]
+pub(crate) fn trace_retry(url: &str, attempt: u32, delay: Duration, result: &Result<RawResponse>) {
+    let error = match result {
+        Ok(raw) => format!("status {}", raw.status),
+        Err(err) => err.to_string(),
+    };
+    tracing::warn!(
+        attempt,
+        url,
+        delay_ms = delay.as_millis() as u64,
+        error = %error,
+        "retrying request"
+    );
+}
+
+/// Returns true if `result` failed in a way a retry may fix.
+pub(crate) fn is_retryable(result: &Result<RawResponse>) -> bool {
+    match result {
+        Ok(raw) => raw.status == 429 || raw.status >= 500,
+        Err(err) => match err.downcast_ref::<IexError>() {
+            Some(IexError::Unreachable { .. })
+            | Some(IexError::RateLimited)
+            | Some(IexError::ServiceUnavailable { .. })
+            | Some(IexError::Http { status: None, .. }) => true,
+            Some(IexError::Http {
+                status: Some(status),
+                ..
+            }) => *status >= 500,
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(jitter: JitterStrategy) -> RetryConfig {
+        RetryConfig {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+            jitter,
+        }
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn no_jitter() {
+        let config = config(JitterStrategy::None);
+        let delays: Vec<Duration> = (0..7).map(|a| config.delay(a, None)).collect();
+        assert_eq!(
+            delays,
+            [
+                ms(100),
+                ms(200),
+                ms(400),
+                ms(800),
+                ms(1600),
+                ms(2000),
+                ms(2000)
+            ]
+        );
+        assert_eq!(config.delay(u32::MAX, None), ms(2000));
+    }
+
+    #[test]
+    fn full_jitter() {
+        let config = config(JitterStrategy::Full);
+        for attempt in 0..8 {
+            let cap = ms(100 * 2u64.pow(attempt)).min(ms(2000));
+            for _ in 0..100 {
+                assert!(config.delay(attempt, None) <= cap);
+            }
+        }
+    }
+
+    #[test]
+    fn equal_jitter() {
+        let config = config(JitterStrategy::Equal);
+        for attempt in 0..8 {
+            let cap = ms(100 * 2u64.pow(attempt)).min(ms(2000));
+            for _ in 0..100 {
+                let delay = config.delay(attempt, None);
+                assert!(delay >= cap / 2 && delay <= cap, "{:?}", delay);
+            }
+        }
+    }
+
+    #[test]
+    fn decorrelated_jitter() {
+        let config = config(JitterStrategy::Decorrelated);
+        let mut previous = None;
+        for attempt in 0..20 {
+            let delay = config.delay(attempt, previous);
+            let high = previous.unwrap_or(ms(100)) * 3;
+            assert!(delay >= ms(100), "{:?}", delay);
+            assert!(delay <= high.min(ms(2000)), "{:?}", delay);
+            previous = Some(delay);
+        }
+    }
+
+    #[test]
+    fn jitter_defaults_to_full() {
+        assert_eq!(RetryConfig::default().jitter, JitterStrategy::Full);
+    }
+
+    #[test]
+    fn retryable() {
+        let raw = |status| RawResponse {
+            status,
+            body: String::new(),
+        };
+        assert!(!is_retryable(&Ok(raw(200))));
+        assert!(!is_retryable(&Ok(raw(404))));
+        assert!(is_retryable(&Ok(raw(429))));
+        assert!(is_retryable(&Ok(raw(503))));
+        assert!(is_retryable(&Err(IexError::RateLimited.into())));
+        assert!(!is_retryable(&Err(IexError::NotFound {
+            symbol: "aapl".to_string()
+        }
+        .into())));
+    }
+
+    #[test]
+    fn retry_budget_refills() {
+        let budget = RetryBudget::new(2, ms(50));
+        assert!(budget.try_acquire());
+        assert!(budget.try_acquire());
+        assert!(!budget.try_acquire());
+        std::thread::sleep(ms(30));
+        assert!(budget.try_acquire());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn retry_budget_caps_client_retries() {
+        use crate::{ClientBuilder, MockTransport, IEX_ENDPOINT};
+        use std::sync::Arc;
+
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let transport = Arc::new(MockTransport::new().with_status(&url, 503, "<html>"));
+        let client = ClientBuilder::new()
+            .transport(transport.clone())
+            .retry(RetryConfig {
+                max_retries: 3,
+                base_delay: ms(1),
+                ..Default::default()
+            })
+            .build()
+            .with_retry_budget(RetryBudget::new(2, Duration::from_secs(3600)));
+
+        assert!(client.quote("aapl").is_err());
+        assert_eq!(transport.hits(&url), 3);
+        assert!(client.quote("aapl").is_err());
+        assert_eq!(transport.hits(&url), 4);
+    }
+
+    #[cfg(all(feature = "blocking", feature = "tracing"))]
+    #[test]
+    fn retries_are_traced() {
+        use crate::{ClientBuilder, MockTransport, IEX_ENDPOINT};
+        use std::fmt::Debug;
+        use std::sync::Arc;
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        #[derive(Default)]
+        struct Attempts(Arc<Mutex<Vec<u64>>>);
+
+        impl Visit for &Attempts {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                if field.name() == "attempt" {
+                    self.0.lock().unwrap().push(value);
+                }
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn Debug) {}
+        }
+
+        impl Subscriber for Attempts {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut &*self);
+            }
+
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let client = ClientBuilder::new()
+            .transport(MockTransport::new().with_status(&url, 503, "<html>"))
+            .retry(RetryConfig {
+                max_retries: 3,
+                base_delay: ms(1),
+                ..Default::default()
+            })
+            .build();
+        let subscriber = Attempts::default();
+        let attempts = subscriber.0.clone();
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(client.quote("aapl").is_err());
+        });
+
+        assert_eq!(*attempts.lock().unwrap(), [1, 2, 3]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn client_retries_transient_errors() {
+        use crate::{ClientBuilder, MockTransport, IEX_ENDPOINT};
+        use std::sync::Arc;
+
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let transport = Arc::new(MockTransport::new().with_status(&url, 503, "<html>"));
+        let client = ClientBuilder::new()
+            .transport(transport.clone())
+            .retry(RetryConfig {
+                max_retries: 2,
+                base_delay: ms(1),
+                ..Default::default()
+            })
+            .build();
+
+        assert!(client.quote("aapl").is_err());
+        assert_eq!(transport.hits(&url), 3);
+
+        let url = format!("{}/stock/zzzz/quote", IEX_ENDPOINT);
+        assert!(client.quote("zzzz").is_err());
+        assert_eq!(transport.hits(&url), 1);
+    }
+}
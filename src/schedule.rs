@@ -0,0 +1,132 @@
+/*! Fair scheduling
+
+With fair scheduling enabled (see `ClientBuilder::fair_scheduling`), a client
+sends one request at a time. Waiting requests are grouped by symbol and the
+symbols take turns, so a watchlist polled under a tight rate budget serves
+every symbol evenly instead of letting the symbols requested first starve
+the others.
+ */
+
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+
+/// Admits one request at a time, round-robin across the keys (symbols) of
+/// the waiting requests and first-come-first-served within a key.
+#[derive(Default)]
+pub(crate) struct FairScheduler {
+    state: Mutex<State>,
+    turn: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    /// True while a request holds the `Permit`.
+    busy: bool,
+    /// Keys with waiting requests in serving order, each with the tickets of
+    /// its requests, oldest first.
+    queues: VecDeque<(String, VecDeque<u64>)>,
+    next_ticket: u64,
+    /// Ticket of the waiting request whose turn it is.
+    granted: Option<u64>,
+}
+
+/// Turn of a request, passed on to the next waiting request when dropped.
+pub(crate) struct Permit<'a> {
+    scheduler: &'a FairScheduler,
+}
+
+impl FairScheduler {
+    /// Blocks until it's the turn of a request for `key`.
+    pub(crate) fn acquire(&self, key: &str) -> Permit<'_> {
+        let mut state = self.state.lock().unwrap();
+        if !state.busy && state.queues.is_empty() {
+            state.busy = true;
+            return Permit { scheduler: self };
+        }
+
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        match state.queues.iter_mut().find(|(k, _)| k == key) {
+            Some((_, tickets)) => tickets.push_back(ticket),
+            None => state
+                .queues
+                .push_back((key.to_string(), VecDeque::from(vec![ticket]))),
+        }
+        while state.granted != Some(ticket) {
+            state = self.turn.wait(state).unwrap();
+        }
+        state.granted = None;
+        Permit { scheduler: self }
+    }
+
+    /// Returns the number of requests waiting for their turn.
+    #[cfg(test)]
+    pub(crate) fn waiting(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.queues.iter().map(|(_, tickets)| tickets.len()).sum()
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        match state.queues.pop_front() {
+            Some((key, mut tickets)) => {
+                state.granted = tickets.pop_front();
+                if !tickets.is_empty() {
+                    state.queues.push_back((key, tickets));
+                }
+                self.turn.notify_all();
+            }
+            None => state.busy = false,
+        }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.scheduler.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn round_robin_across_keys() {
+        let scheduler = FairScheduler::default();
+        let served = Mutex::new(Vec::new());
+
+        thread::scope(|s| {
+            let (scheduler, served) = (&scheduler, &served);
+            let permit = scheduler.acquire("busy");
+            for (i, &key) in ["aapl", "aapl", "aapl", "fb", "fb", "snap"]
+                .iter()
+                .enumerate()
+            {
+                s.spawn(move || {
+                    let _permit = scheduler.acquire(key);
+                    served.lock().unwrap().push(key);
+                });
+                while scheduler.waiting() < i + 1 {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+            drop(permit);
+        });
+
+        assert_eq!(
+            served.into_inner().unwrap(),
+            ["aapl", "fb", "snap", "aapl", "fb", "aapl"]
+        );
+    }
+
+    #[test]
+    fn idle_scheduler_admits_immediately() {
+        let scheduler = FairScheduler::default();
+        drop(scheduler.acquire("aapl"));
+        drop(scheduler.acquire("aapl"));
+        assert_eq!(scheduler.waiting(), 0);
+    }
+}
@@ -1,28 +1,363 @@
-/*!
+/*! IEX Stats
  */
 
-use super::Result;
+#[cfg(feature = "blocking")]
+use super::concurrency::join;
+#[cfg(feature = "blocking")]
+use super::Client;
+use super::{from_millis, to_millis, Result, IEX_ENDPOINT};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use serde_aux::prelude::*;
+use std::fmt::Display;
+use std::str::FromStr;
+#[cfg(feature = "blocking")]
+use std::thread;
 
-pub struct Stats;
+/// A statistic of the current trading day, with the time it was last
+/// updated. IEX sends some values as numeric strings.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(
+    rename_all = "camelCase",
+    bound(deserialize = "T: FromStr + Deserialize<'de>, T::Err: Display")
+)]
+pub struct IntradayStat<T> {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub value: T,
+    #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+    pub last_updated: DateTime<Utc>,
+}
 
-impl Stats {
-    pub fn intraday(&self) -> Result<()> {
-        Ok(())
+/// Trading statistics of IEX for the current day, as reported by
+/// `/stats/intraday`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntradayStats {
+    /// Shares traded on IEX, excluding routed shares.
+    pub volume: IntradayStat<u64>,
+    /// Number of symbols traded on IEX.
+    pub symbols_traded: IntradayStat<u64>,
+    /// Shares routed by IEX to other venues.
+    pub routed_volume: IntradayStat<u64>,
+    /// Dollar value traded on IEX.
+    pub notional: IntradayStat<f64>,
+    /// IEX's percentage of the total US equities market volume.
+    pub market_share: IntradayStat<f64>,
+}
+
+/// A record of IEX trading activity, with recent values to compare it to.
+/// IEX sends some values as numeric strings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordStat {
+    /// Record value.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub record_value: f64,
+    /// Date the record was set.
+    pub record_date: NaiveDate,
+    /// Value of the previous trading day.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub previous_day_value: f64,
+    /// Average value over the last 30 trading days.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub avg30_value: f64,
+}
+
+/// Records of IEX trading activity, as reported by `/stats/records`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordStats {
+    /// Shares traded on IEX, excluding routed shares.
+    pub volume: RecordStat,
+    /// Number of symbols traded on IEX.
+    pub symbols_traded: RecordStat,
+    /// Shares routed by IEX to other venues.
+    pub routed_volume: RecordStat,
+    /// Dollar value traded on IEX.
+    pub notional: RecordStat,
+}
+
+/// Trading statistics of IEX for one of the last trading days, as reported
+/// by `/stats/recent`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentStats {
+    /// Trading day.
+    pub date: NaiveDate,
+    /// Shares traded on IEX, excluding routed shares.
+    pub volume: u64,
+    /// Shares routed by IEX to other venues.
+    pub routed_volume: u64,
+    /// IEX's percentage of the total US equities market volume.
+    pub market_share: f64,
+    /// True if the day was a half day.
+    pub is_halfday: bool,
+    /// Shares traded on IEX excluding auction and hidden volume.
+    pub lit_volume: u64,
+}
+
+/// Trading statistics of IEX for a month, as reported by
+/// `/stats/historical`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalStats {
+    pub average_daily_volume: f64,
+    pub average_daily_routed_volume: f64,
+    pub average_market_share: f64,
+    pub average_order_size: f64,
+    pub average_fill_size: f64,
+    pub bin100_percent: f64,
+    pub bin101_percent: f64,
+    pub bin200_percent: f64,
+    pub bin300_percent: f64,
+    pub bin400_percent: f64,
+    pub bin500_percent: f64,
+    pub bin1000_percent: f64,
+    pub bin5000_percent: f64,
+    pub bin10000_percent: f64,
+    pub bin10000_trades: f64,
+    pub bin20000_trades: f64,
+    pub bin50000_trades: f64,
+    pub unique_symbols_traded: f64,
+    pub block_percent: f64,
+    pub self_cross_percent: f64,
+    pub etf_percent: f64,
+    pub large_cap_percent: f64,
+    pub mid_cap_percent: f64,
+    pub small_cap_percent: f64,
+}
+
+/// Intraday, recent and record statistics, fetched together by
+/// `Stats::dashboard`. Each section keeps its own result so one failed
+/// request doesn't hide the others.
+#[derive(Debug)]
+pub struct StatsDashboard {
+    pub intraday: Result<IntradayStats>,
+    pub recent: Result<Vec<RecentStats>>,
+    pub records: Result<RecordStats>,
+}
+
+#[cfg(feature = "blocking")]
+pub struct Stats<'a> {
+    pub(crate) client: &'a Client,
+}
+
+#[cfg(feature = "blocking")]
+impl<'a> Stats<'a> {
+    /// Returns the trading statistics of IEX for the current day.
+    pub fn intraday(&self) -> Result<IntradayStats> {
+        self.client
+            .get(&format!("{}/stats/intraday", IEX_ENDPOINT))?
+            .try_into()
     }
 
-    pub fn recent(&self) -> Result<()> {
-        Ok(())
+    /// Returns the trading statistics of IEX for the last several trading
+    /// days, most recent first.
+    pub fn recent(&self) -> Result<Vec<RecentStats>> {
+        self.client
+            .get(&format!("{}/stats/recent", IEX_ENDPOINT))?
+            .into_vec()
     }
 
-    pub fn records(&self) -> Result<()> {
-        Ok(())
+    /// Returns the records of IEX trading activity.
+    pub fn records(&self) -> Result<RecordStats> {
+        self.client
+            .get(&format!("{}/stats/records", IEX_ENDPOINT))?
+            .try_into()
     }
 
-    pub fn historical_summary(&self) -> Result<()> {
-        Ok(())
+    /// Fetches the intraday, recent and record statistics concurrently.
+    ///
+    /// Returns an error only if all three requests fail; otherwise the
+    /// failures are reported per section.
+    pub fn dashboard(&self) -> Result<StatsDashboard> {
+        let (intraday, recent, records) = thread::scope(|s| {
+            let intraday = s.spawn(|| self.intraday());
+            let recent = s.spawn(|| self.recent());
+            let records = s.spawn(|| self.records());
+            (join(intraday), join(recent), join(records))
+        });
+
+        match (intraday, recent, records) {
+            (Err(e), Err(_), Err(_)) => Err(e),
+            (intraday, recent, records) => Ok(StatsDashboard {
+                intraday,
+                recent,
+                records,
+            }),
+        }
+    }
+
+    /// Returns the trading statistics of IEX for the month of `date`, or
+    /// for the prior month if `date` is `None`.
+    pub fn historical(&self, date: Option<NaiveDate>) -> Result<Vec<HistoricalStats>> {
+        self.client.get(&historical_url(date))?.into_vec()
     }
 
     pub fn historical_daily(&self) -> Result<()> {
         Ok(())
     }
 }
+
+/// Builds the URL of a `/stats/historical` request for the month of `date`.
+fn historical_url(date: Option<NaiveDate>) -> String {
+    match date {
+        Some(date) => format!(
+            "{}/stats/historical?date={}",
+            IEX_ENDPOINT,
+            date.format("%Y%m")
+        ),
+        None => format!("{}/stats/historical", IEX_ENDPOINT),
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::MockTransport;
+
+    const INTRADAY: &str = r#"{
+        "volume": {"value": 26908038, "lastUpdated": 1480433817317},
+        "symbolsTraded": {"value": 4089, "lastUpdated": 1480433817317},
+        "routedVolume": {"value": "4770802", "lastUpdated": 1480433817317},
+        "notional": {"value": "1036565991.51", "lastUpdated": 1480433817317},
+        "marketShare": {"value": 0.01804, "lastUpdated": 1480433817317}
+    }"#;
+
+    #[test]
+    fn deserialize_intraday() {
+        let stats: IntradayStats = serde_json::from_str(INTRADAY).unwrap();
+        assert_eq!(stats.volume.value, 26908038);
+        assert_eq!(stats.symbols_traded.value, 4089);
+        assert_eq!(stats.routed_volume.value, 4770802);
+        assert_eq!(stats.notional.value, 1036565991.51);
+        assert_eq!(stats.market_share.value, 0.01804);
+        assert_eq!(stats.volume.last_updated.timestamp_millis(), 1480433817317);
+    }
+
+    #[test]
+    fn deserialize_recent() {
+        let json_data = r#"[
+            {"date":"2017-11-24","volume":81345233,"routedVolume":21839413,"marketShare":0.02231,"isHalfday":true,"litVolume":19584726},
+            {"date":"2017-11-22","volume":128048723,"routedVolume":38314207,"marketShare":0.01769,"isHalfday":false,"litVolume":30520534}
+        ]"#;
+
+        let stats: Vec<RecentStats> = serde_json::from_str(json_data).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[0].date,
+            NaiveDate::from_ymd_opt(2017, 11, 24).unwrap()
+        );
+        assert!(stats[0].is_halfday);
+        assert!(!stats[1].is_halfday);
+        assert_eq!(stats[1].volume, 128048723);
+        assert_eq!(stats[1].lit_volume, 30520534);
+        assert_eq!(stats[1].market_share, 0.01769);
+    }
+
+    const RECORDS: &str = r#"{
+        "volume": {"recordValue": 233000477, "recordDate": "2016-01-20", "previousDayValue": 99594714, "avg30Value": 138634204.5},
+        "symbolsTraded": {"recordValue": "6046", "recordDate": "2016-11-10", "previousDayValue": "5500", "avg30Value": "5617"},
+        "routedVolume": {"recordValue": 74855222, "recordDate": "2016-11-10", "previousDayValue": 29746476, "avg30Value": 44520084.2},
+        "notional": {"recordValue": "9887832327.8355", "recordDate": "2016-11-10", "previousDayValue": 4175710684.3897, "avg30Value": 5771412395.4944}
+    }"#;
+
+    #[test]
+    fn deserialize_records() {
+        let stats: RecordStats = serde_json::from_str(RECORDS).unwrap();
+        assert_eq!(stats.volume.record_value, 233000477.0);
+        assert_eq!(
+            stats.volume.record_date,
+            NaiveDate::from_ymd_opt(2016, 1, 20).unwrap()
+        );
+        assert_eq!(stats.volume.avg30_value, 138634204.5);
+        assert_eq!(stats.symbols_traded.record_value, 6046.0);
+        assert_eq!(stats.symbols_traded.previous_day_value, 5500.0);
+        assert_eq!(stats.notional.record_value, 9887832327.8355);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn dashboard() {
+        let transport = MockTransport::new()
+            .with(&format!("{}/stats/intraday", IEX_ENDPOINT), INTRADAY)
+            .with(&format!("{}/stats/recent", IEX_ENDPOINT), "[]")
+            .with(&format!("{}/stats/records", IEX_ENDPOINT), RECORDS);
+        let client = Client::with_transport(transport);
+
+        let dashboard = client.iex_stats().dashboard().unwrap();
+        assert_eq!(dashboard.intraday.unwrap().symbols_traded.value, 4089);
+        assert!(dashboard.recent.unwrap().is_empty());
+        assert_eq!(dashboard.records.unwrap().volume.record_value, 233000477.0);
+
+        let transport =
+            MockTransport::new().with(&format!("{}/stats/intraday", IEX_ENDPOINT), INTRADAY);
+        let dashboard = Client::with_transport(transport)
+            .iex_stats()
+            .dashboard()
+            .unwrap();
+        assert!(dashboard.intraday.is_ok());
+        assert!(dashboard.recent.is_err());
+        assert!(dashboard.records.is_err());
+
+        let client = Client::with_transport(MockTransport::new());
+        assert!(client.iex_stats().dashboard().is_err());
+    }
+
+    #[test]
+    fn deserialize_historical() {
+        let json_data = r#"[{
+            "averageDailyVolume": 112247378.5,
+            "averageDailyRoutedVolume": 34282226.24,
+            "averageMarketShare": 0,
+            "averageOrderSize": 493,
+            "averageFillSize": 287,
+            "bin100Percent": 0.61,
+            "bin101Percent": 0.16,
+            "bin200Percent": 0.06,
+            "bin300Percent": 0.03,
+            "bin400Percent": 0.02,
+            "bin500Percent": 0.04,
+            "bin1000Percent": 0.05,
+            "bin5000Percent": 0.01,
+            "bin10000Percent": 0.01,
+            "bin10000Trades": 4666,
+            "bin20000Trades": 1568,
+            "bin50000Trades": 231,
+            "uniqueSymbolsTraded": 7419,
+            "blockPercent": 0.08,
+            "selfCrossPercent": 0.02,
+            "etfPercent": 0.12,
+            "largeCapPercent": 0.40,
+            "midCapPercent": 0.33,
+            "smallCapPercent": 0.19
+        }]"#;
+
+        let stats: Vec<HistoricalStats> = serde_json::from_str(json_data).unwrap();
+        assert_eq!(stats[0].average_daily_volume, 112247378.5);
+        assert_eq!(stats[0].bin100_percent, 0.61);
+        assert_eq!(stats[0].unique_symbols_traded, 7419.0);
+    }
+
+    #[test]
+    fn historical_month() {
+        let date = NaiveDate::from_ymd_opt(2017, 5, 18);
+        assert_eq!(
+            historical_url(date),
+            format!("{}/stats/historical?date=201705", IEX_ENDPOINT)
+        );
+        assert_eq!(
+            historical_url(None),
+            format!("{}/stats/historical", IEX_ENDPOINT)
+        );
+    }
+
+    #[test]
+    fn client_intraday() {
+        let url = format!("{}/stats/intraday", IEX_ENDPOINT);
+        let client = Client::with_transport(MockTransport::new().with(&url, INTRADAY));
+
+        let stats = client.iex_stats().intraday().unwrap();
+        assert_eq!(stats.routed_volume.value, 4770802);
+    }
+}
@@ -1,7 +1,23 @@
 /*! Endpoint.
  */
 
-use super::Endpoint;
+#[cfg(feature = "blocking")]
+use super::concurrency::map_concurrent;
+use super::types::Stats;
+#[cfg(feature = "blocking")]
+use super::Client;
+use super::{
+    money_to_f64, normalize_symbols, ChartDataPoint, Company, CryptoQuote, DelayedQuote, Dividend,
+    Earnings, EffectiveSpread, Endpoint, Financials, IexError, IntradayBar, IpoCalendar, IpoEntry,
+    Logo, MyError, News, Previous, Quote, Relevant, Response, Result, Split, StockBook,
+    TodayEarnings, VolumeByVenue, OHLC,
+};
+use chrono::{DateTime, NaiveDate};
+use failure::ResultExt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io;
 
 // Endpoints
 //   * Stocks
@@ -38,17 +54,18 @@ use super::Endpoint;
 //       - Time Series
 //       - Volume by Venue
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 /// The `StocksEndpoint` enum allows for HTTP requests matching to a IEX Stocks Endpoint API.
 // TODO:(Request): Add documentation from IEX website.
-// TODO: use display_percent
 pub enum StocksEndpoint<'a> {
     Book,
     Chart {
         duration: Duration<'a>,
-        params: Option<Vec<ChartParam>>,
+        params: Option<ChartParams>,
     },
     Company,
+    /// Quotes of the supported cryptocurrency pairs, market-wide.
+    Crypto,
     DelayedQuote,
     Dividends {
         duration: Duration<'a>,
@@ -58,10 +75,11 @@ pub enum StocksEndpoint<'a> {
     Financials,
     List {
         param: ListParam,
+        params: Option<ListParams>,
     },
     Logo,
     News {
-        range: Option<i32>,
+        range: Option<NewsCount>,
     },
     Ohlc,
     Peers,
@@ -73,6 +91,12 @@ pub enum StocksEndpoint<'a> {
         duration: Duration<'a>,
     },
     Stats,
+    /// Earnings reported today, market-wide.
+    TodayEarnings,
+    /// IPOs expected today, market-wide.
+    TodayIpos,
+    /// IPOs expected in the coming weeks, market-wide.
+    UpcomingIpos,
 
     // TODO: IEX Short Interest List
     /// IEX Regulation SHO Threshold Securities List
@@ -84,28 +108,130 @@ pub enum StocksEndpoint<'a> {
     VolumeByVenue,
 }
 
+/// Most news feeds `Client::news_multi` fetches at once.
+#[cfg(feature = "blocking")]
+const MAX_CONCURRENT_NEWS: usize = 4;
+
+/// Endpoints that take no parameters.
+const SIMPLE_ENDPOINTS: [StocksEndpoint<'static>; 20] = [
+    StocksEndpoint::Book,
+    StocksEndpoint::Company,
+    StocksEndpoint::Crypto,
+    StocksEndpoint::DelayedQuote,
+    StocksEndpoint::Earnings,
+    StocksEndpoint::EffectiveSpread,
+    StocksEndpoint::Financials,
+    StocksEndpoint::Logo,
+    StocksEndpoint::Ohlc,
+    StocksEndpoint::Peers,
+    StocksEndpoint::Previous,
+    StocksEndpoint::Price,
+    StocksEndpoint::Quote,
+    StocksEndpoint::Relevant,
+    StocksEndpoint::Stats,
+    StocksEndpoint::TimeSeries,
+    StocksEndpoint::TodayEarnings,
+    StocksEndpoint::TodayIpos,
+    StocksEndpoint::UpcomingIpos,
+    StocksEndpoint::VolumeByVenue,
+];
+
+impl StocksEndpoint<'static> {
+    /// Returns the tokens of all supported endpoints, see `token`.
+    pub fn all_variants() -> Vec<&'static str> {
+        vec![
+            "book",
+            "chart",
+            "company",
+            "crypto",
+            "delayed-quote",
+            "dividends",
+            "earnings",
+            "effective-spread",
+            "financials",
+            "list",
+            "logo",
+            "news",
+            "ohlc",
+            "peers",
+            "previous",
+            "price",
+            "quote",
+            "relevant",
+            "splits",
+            "stats",
+            "time-series",
+            "threshold-securities",
+            "today-earnings",
+            "today-ipos",
+            "upcoming-ipos",
+            "volume-by-venue",
+        ]
+    }
+
+    /// Returns the endpoint named `token` if it takes no parameters, e.g.
+    /// `StocksEndpoint::Quote` for `"quote"`.
+    pub fn from_token(token: &str) -> Option<StocksEndpoint<'static>> {
+        SIMPLE_ENDPOINTS
+            .iter()
+            .find(|e| e.token() == token)
+            .cloned()
+    }
+}
+
+impl<'a> StocksEndpoint<'a> {
+    /// Returns the path segment naming the endpoint, e.g. `"delayed-quote"`,
+    /// without any of its parameters.
+    pub fn token(&self) -> &'static str {
+        match self {
+            StocksEndpoint::Book => "book",
+            StocksEndpoint::Chart { .. } => "chart",
+            StocksEndpoint::Company => "company",
+            StocksEndpoint::Crypto => "crypto",
+            StocksEndpoint::DelayedQuote => "delayed-quote",
+            StocksEndpoint::Dividends { .. } => "dividends",
+            StocksEndpoint::Earnings => "earnings",
+            StocksEndpoint::EffectiveSpread => "effective-spread",
+            StocksEndpoint::Financials => "financials",
+            StocksEndpoint::List { .. } => "list",
+            StocksEndpoint::Logo => "logo",
+            StocksEndpoint::News { .. } => "news",
+            StocksEndpoint::Ohlc => "ohlc",
+            StocksEndpoint::Peers => "peers",
+            StocksEndpoint::Previous => "previous",
+            StocksEndpoint::Price => "price",
+            StocksEndpoint::Quote => "quote",
+            StocksEndpoint::Relevant => "relevant",
+            StocksEndpoint::Splits { .. } => "splits",
+            StocksEndpoint::Stats => "stats",
+            StocksEndpoint::TimeSeries => "time-series",
+            StocksEndpoint::ThresholdSecurities { .. } => "threshold-securities",
+            StocksEndpoint::TodayEarnings => "today-earnings",
+            StocksEndpoint::TodayIpos => "today-ipos",
+            StocksEndpoint::UpcomingIpos => "upcoming-ipos",
+            StocksEndpoint::VolumeByVenue => "volume-by-venue",
+        }
+    }
+}
+
 impl<'a> Endpoint for StocksEndpoint<'a> {
     fn to_endpoint(self) -> String {
         match self {
             StocksEndpoint::Book => String::from("book"),
 
-            StocksEndpoint::Chart { duration, params } => format!(
-                "chart/{}?{chart_params}",
-                duration.to_string(),
-                chart_params = match params {
-                    Some(parameters) => {
-                        let chart_params: String = parameters
-                            .iter()
-                            .map(|param| param.to_string() + "/")
-                            .collect();
-                        chart_params
-                    }
-                    None => String::from(""),
+            StocksEndpoint::Chart { duration, params } => {
+                let query = params.map(|p| p.to_query()).unwrap_or_default();
+                if query.is_empty() {
+                    format!("chart/{}", duration.to_string())
+                } else {
+                    format!("chart/{}?{}", duration.to_string(), query)
                 }
-            ),
+            }
 
             StocksEndpoint::Company => String::from("company"),
 
+            StocksEndpoint::Crypto => String::from("crypto"),
+
             StocksEndpoint::DelayedQuote => String::from("delayed-quote"),
 
             StocksEndpoint::Dividends { duration } => format!("dividends/{}", duration.to_string()),
@@ -116,14 +242,21 @@ impl<'a> Endpoint for StocksEndpoint<'a> {
 
             StocksEndpoint::Financials => String::from("financials"),
 
-            StocksEndpoint::List { param } => format!("list/{}", param.to_string()),
+            StocksEndpoint::List { param, params } => {
+                let query = params.map(|p| p.to_query()).unwrap_or_default();
+                if query.is_empty() {
+                    format!("list/{}", param.to_string())
+                } else {
+                    format!("list/{}?{}", param.to_string(), query)
+                }
+            }
 
             StocksEndpoint::Logo => String::from("logo"),
 
-            StocksEndpoint::News { range } => format!(
-                "news/last/{}",
-                range.map(|r| r.to_string()).unwrap_or("".to_string()),
-            ),
+            StocksEndpoint::News { range } => match range {
+                Some(count) => format!("news/last/{}", count.get()),
+                None => String::from("news"),
+            },
 
             StocksEndpoint::Ohlc => String::from("ohlc"),
 
@@ -143,17 +276,81 @@ impl<'a> Endpoint for StocksEndpoint<'a> {
 
             StocksEndpoint::TimeSeries => String::from("time-series"),
 
-            StocksEndpoint::ThresholdSecurities { date } => format!(
-                "threshold-securities/{}",
-                date.unwrap_or(Duration::None).to_string(),
-            ),
+            StocksEndpoint::ThresholdSecurities { date } => {
+                match date.map(|d| d.to_string()).filter(|d| !d.is_empty()) {
+                    Some(date) => format!("threshold-securities/{}", date),
+                    None => String::from("threshold-securities"),
+                }
+            }
+
+            StocksEndpoint::TodayEarnings => String::from("today-earnings"),
+
+            StocksEndpoint::TodayIpos => String::from("today-ipos"),
+
+            StocksEndpoint::UpcomingIpos => String::from("upcoming-ipos"),
 
             StocksEndpoint::VolumeByVenue => String::from("volume-by-venue"),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+/// Query parameters that apply to any Stocks API request.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// Fields to return, e.g. `symbol` and `latestPrice`. All fields are
+    /// returned when empty.
+    pub filter: Vec<String>,
+    /// API token of an IEX user account.
+    pub token: Option<String>,
+    /// Response format, e.g. `csv`. JSON when `None`.
+    pub format: Option<String>,
+    /// If true, percentage values are multiplied by 100.
+    pub display_percent: Option<bool>,
+}
+
+impl RequestOptions {
+    /// Returns the options as an URL query string, without the leading `?`.
+    pub fn to_query(&self) -> String {
+        let mut query = Vec::new();
+        if !self.filter.is_empty() {
+            query.push(format!("filter={}", self.filter.join(",")));
+        }
+        if let Some(ref token) = self.token {
+            query.push(format!("token={}", token));
+        }
+        if let Some(ref format) = self.format {
+            query.push(format!("format={}", format));
+        }
+        if let Some(display_percent) = self.display_percent {
+            query.push(format!("displayPercent={}", display_percent));
+        }
+        query.join("&")
+    }
+}
+
+/// Builds the URL of the `endpoint` request for `symbol`, relative to the
+/// API root `base`.
+pub fn build_stock_url(
+    base: &str,
+    symbol: &str,
+    endpoint: &StocksEndpoint,
+    opts: &RequestOptions,
+) -> String {
+    let mut url = format!(
+        "{base}/stock/{symbol}/{endpoint}",
+        base = base,
+        symbol = symbol,
+        endpoint = endpoint.to_endpoint()
+    );
+    let query = opts.to_query();
+    if !query.is_empty() {
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(&query);
+    }
+    url
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Duration<'a> {
     FiveYears,
     TwoYears,
@@ -186,19 +383,28 @@ impl<'a> ToString for Duration<'a> {
     }
 }
 
+impl<'a> Duration<'a> {
+    /// Returns true if IEX honors the `chartLast` parameter for charts over
+    /// the duration, which excludes single days requested by date.
+    fn supports_chart_last(&self) -> bool {
+        !matches!(self, Duration::Date(_) | Duration::Dynamic | Duration::None)
+    }
+}
+
 impl<'a> Default for Duration<'a> {
     fn default() -> Duration<'a> {
         Duration::OneMonth
     }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ListParam {
     MostActive,
     Gainers,
     Losers,
     IexVolume,
     IexPercent,
+    InFocus,
 }
 
 impl ToString for ListParam {
@@ -209,36 +415,466 @@ impl ToString for ListParam {
             ListParam::Losers => String::from("losers"),
             ListParam::IexVolume => String::from("iexvolume"),
             ListParam::IexPercent => String::from("iexpercent"),
+            ListParam::InFocus => String::from("infocus"),
+        }
+    }
+}
+
+/// Optional query parameters accepted by the market list endpoints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListParams {
+    /// If true, percentage values are multiplied by 100.
+    pub display_percent: Option<bool>,
+    /// Number of quotes to return, 10 by default.
+    pub limit: Option<u32>,
+}
+
+impl ListParams {
+    /// Returns the parameters as an URL query string, without the leading `?`.
+    pub fn to_query(&self) -> String {
+        let mut query = Vec::new();
+        if let Some(display_percent) = self.display_percent {
+            query.push(format!("displayPercent={}", display_percent));
+        }
+        if let Some(limit) = self.limit {
+            query.push(format!("listLimit={}", limit));
+        }
+        query.join("&")
+    }
+}
+
+/// Number of news items to request, between 1 and 50.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NewsCount(u32);
+
+impl NewsCount {
+    /// Largest number of news items IEX returns.
+    pub const MAX: u32 = 50;
+
+    /// Returns an error if `count` is zero or above `NewsCount::MAX`.
+    pub fn new(count: u32) -> Result<NewsCount> {
+        if count == 0 || count > NewsCount::MAX {
+            return Err(MyError::from("news count must be between 1 and 50").into());
         }
+        Ok(NewsCount(count))
+    }
+
+    /// Caps `count` to the 1 to `NewsCount::MAX` range.
+    pub fn clamped(count: u32) -> NewsCount {
+        NewsCount(count.clamp(1, NewsCount::MAX))
+    }
+
+    pub fn get(self) -> u32 {
+        self.0
     }
 }
 
-#[derive(PartialEq, Eq)]
-pub enum ChartParam {
+/// Optional query parameters accepted by the chart endpoint.
+///
+/// Only the parameters that are set are sent to IEX, e.g.
+/// `ChartParams { last: Some(10), ..Default::default() }` produces
+/// `chartLast=10`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChartParams {
     /// If true, 1d chart will reset at midnight instead of the default behavior of 9:30am ET.
-    Reset(bool),
+    pub reset: Option<bool>,
     /// If true, runs a polyline simplification using the Douglas-Peucker algorithm. This is useful if plotting sparkline charts.
-    Simplify(bool),
-    /// If passed, chart data will return every Nth element as defined by `Interval`.
-    Interval(usize),
+    pub simplify: Option<bool>,
+    /// If passed, chart data will return every Nth element as defined by `interval`.
+    pub interval: Option<u32>,
     /// If true, changeOverTime and marketChangeOverTime will be relative to previous day close instead of the first value.
-    ChangeFromClose(bool),
+    pub change_from_close: Option<bool>,
     /// If passed, chart data will return the last N elements.
-    Last(usize),
+    pub last: Option<u32>,
+    /// If true, a chart by date returns a single point aggregating the day
+    /// instead of its minute bars.
+    pub by_day: Option<bool>,
 }
 
-impl ToString for ChartParam {
-    fn to_string(&self) -> String {
-        match self {
-            ChartParam::Reset(res) => format!("chartReset={}", res),
-            ChartParam::Simplify(res) => format!("chartSimplify={}", res),
-            ChartParam::Interval(res) => format!("chartInterval={}", res),
-            ChartParam::ChangeFromClose(res) => format!("changeFromClose={}", res),
-            ChartParam::Last(res) => format!("chartLast={}", res),
+impl ChartParams {
+    /// Returns the parameters as an URL query string, without the leading `?`.
+    pub fn to_query(&self) -> String {
+        let mut query = Vec::new();
+        if let Some(reset) = self.reset {
+            query.push(format!("chartReset={}", reset));
+        }
+        if let Some(simplify) = self.simplify {
+            query.push(format!("chartSimplify={}", simplify));
+        }
+        if let Some(interval) = self.interval {
+            query.push(format!("chartInterval={}", interval));
+        }
+        if let Some(change_from_close) = self.change_from_close {
+            query.push(format!("changeFromClose={}", change_from_close));
         }
+        if let Some(last) = self.last {
+            query.push(format!("chartLast={}", last));
+        }
+        if let Some(by_day) = self.by_day {
+            query.push(format!("chartByDay={}", by_day));
+        }
+        query.join("&")
     }
 }
 
+/// Typed helpers around `stocks_request`.
+#[cfg(feature = "blocking")]
+impl Client {
+    /// Issues `req` for `symbol` and deserializes the response into `T`. A
+    /// deserialization failure is reported with the `name` of the requested
+    /// type and the symbol.
+    fn typed_stocks_request<T>(&self, name: &str, symbol: &str, req: StocksEndpoint) -> Result<T>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        Ok(self
+            .stocks_request(symbol, req)?
+            .try_into_with(self.strictness())
+            .with_context(|e| format!("failed to deserialize {} for {}: {}", name, symbol, e))
+            .map_err(MyError::from)?)
+    }
+
+    /// Returns the response of `req` for `symbol` deserialized as `T`,
+    /// together with the untouched response, e.g. for logging the raw
+    /// payload. The body is fetched and parsed only once, as strictly as
+    /// `Client::strictness` says.
+    pub fn fetch_typed<T>(&self, symbol: &str, req: StocksEndpoint) -> Result<(T, Response)>
+    where
+        T: for<'de> serde::Deserialize<'de>,
+    {
+        let response = self.stocks_request(symbol, req)?;
+        let value = response
+            .deserialize_with(self.strictness())
+            .with_context(|e| format!("failed to deserialize response for {}: {}", symbol, e))
+            .map_err(MyError::from)?;
+        Ok((value, response))
+    }
+
+    /// Returns the quote, IEX order book and recent trades of `symbol`.
+    pub fn book(&self, symbol: &str) -> Result<StockBook> {
+        self.typed_stocks_request("StockBook", symbol, StocksEndpoint::Book)
+    }
+
+    /// Returns the chart of `symbol` over `duration`.
+    pub fn chart(
+        &self,
+        symbol: &str,
+        duration: Duration,
+        params: Option<ChartParams>,
+    ) -> Result<Vec<ChartDataPoint>> {
+        let req = StocksEndpoint::Chart { duration, params };
+        let raw = self.stocks_request_raw(symbol.to_string(), &req, &RequestOptions::default())?;
+        Ok(parse_chart(&raw.body)
+            .with_context(|e| format!("failed to deserialize Chart for {}: {}", symbol, e))
+            .map_err(MyError::from)?)
+    }
+
+    /// Returns the last `n` points of the chart of `symbol` over `duration`.
+    /// Only those points are requested, with `chartLast`, unless the
+    /// duration doesn't support it, in which case the chart is truncated
+    /// once fetched.
+    pub fn chart_last(
+        &self,
+        symbol: &str,
+        duration: Duration,
+        n: u32,
+    ) -> Result<Vec<ChartDataPoint>> {
+        let params = if duration.supports_chart_last() {
+            Some(ChartParams {
+                last: Some(n),
+                ..Default::default()
+            })
+        } else {
+            None
+        };
+        let mut chart = self.chart(symbol, duration, params)?;
+        let excess = chart.len().saturating_sub(n as usize);
+        chart.drain(..excess);
+        Ok(chart)
+    }
+
+    /// Returns the minute bars of the current or latest trading day of
+    /// `symbol`.
+    pub fn intraday_chart(&self, symbol: &str) -> Result<Vec<IntradayBar>> {
+        let req = StocksEndpoint::Chart {
+            duration: Duration::OneDay,
+            params: None,
+        };
+        self.typed_stocks_request("IntradayChart", symbol, req)
+    }
+
+    /// Returns the minute bars of `symbol` on the past trading day `date`.
+    pub fn intraday_chart_on(&self, symbol: &str, date: NaiveDate) -> Result<Vec<IntradayBar>> {
+        let date = date.format("%Y%m%d").to_string();
+        let req = StocksEndpoint::Chart {
+            duration: Duration::Date(&date),
+            params: None,
+        };
+        self.typed_stocks_request("IntradayChart", symbol, req)
+    }
+
+    /// Returns a single point aggregating the trading of `symbol` on the
+    /// past day `date`, using `chartByDay`, or `None` if it didn't trade
+    /// that day.
+    pub fn day_chart_on(&self, symbol: &str, date: NaiveDate) -> Result<Option<ChartDataPoint>> {
+        let date = date.format("%Y%m%d").to_string();
+        let params = ChartParams {
+            by_day: Some(true),
+            ..Default::default()
+        };
+        let chart = self.chart(symbol, Duration::Date(&date), Some(params))?;
+        Ok(chart.into_iter().next())
+    }
+
+    /// Returns the chart of `symbol` over `duration` with `adjusted_close` set
+    /// on every point, using the splits and dividends of the same window. See
+    /// `adjust_closes`.
+    pub fn adjusted_chart(&self, symbol: &str, duration: Duration) -> Result<Vec<ChartDataPoint>> {
+        let mut chart = self.chart(symbol, duration, None)?;
+        let splits = self.splits(symbol, duration)?;
+        let dividends = self.dividends(symbol, duration)?;
+        chart.sort_by(|a, b| a.date.cmp(&b.date));
+        adjust_closes(&mut chart, &splits, &dividends);
+        Ok(chart)
+    }
+
+    /// Returns the quotes of the market list `param`, e.g. the most active
+    /// symbols.
+    ///
+    /// `displayPercent` is set as configured by
+    /// `ClientBuilder::display_percent`, unless `params` set it.
+    pub fn list(&self, param: ListParam, params: Option<ListParams>) -> Result<Vec<Quote>> {
+        let mut params = params.unwrap_or_default();
+        if params.display_percent.is_none() && self.display_percent() {
+            params.display_percent = Some(true);
+        }
+        let params = if params == ListParams::default() {
+            None
+        } else {
+            Some(params)
+        };
+        let mut quotes: Vec<Quote> = self
+            .stocks_request("market", StocksEndpoint::List { param, params })?
+            .into_vec_with(self.strictness())
+            .with_context(|e| format!("failed to deserialize list {}: {}", param.to_string(), e))
+            .map_err(MyError::from)?;
+        if params.and_then(|p| p.display_percent) == Some(true) {
+            quotes.iter_mut().for_each(Quote::mark_display_percent);
+        }
+        Ok(quotes)
+    }
+
+    /// Returns the companies reporting earnings today, before the open and
+    /// after the close.
+    pub fn earnings_today(&self) -> Result<TodayEarnings> {
+        self.typed_stocks_request("TodayEarnings", "market", StocksEndpoint::TodayEarnings)
+    }
+
+    /// Returns the IPOs expected in the coming weeks.
+    pub fn upcoming_ipos(&self) -> Result<Vec<IpoEntry>> {
+        let calendar: IpoCalendar =
+            self.typed_stocks_request("IpoCalendar", "market", StocksEndpoint::UpcomingIpos)?;
+        Ok(calendar.raw_data)
+    }
+
+    /// Returns the IPOs expected today.
+    pub fn today_ipos(&self) -> Result<Vec<IpoEntry>> {
+        let calendar: IpoCalendar =
+            self.typed_stocks_request("IpoCalendar", "market", StocksEndpoint::TodayIpos)?;
+        Ok(calendar.raw_data)
+    }
+
+    /// Returns the quote of the cryptocurrency pair `symbol`, e.g.
+    /// `btcusdt`.
+    pub fn crypto_quote(&self, symbol: &str) -> Result<CryptoQuote> {
+        self.typed_stocks_request("CryptoQuote", symbol, StocksEndpoint::Quote)
+    }
+
+    /// Returns the quotes of all the cryptocurrency pairs IEX supports, in
+    /// the order IEX sends them.
+    pub fn crypto_list(&self) -> Result<Vec<CryptoQuote>> {
+        self.typed_stocks_request("CryptoQuote", "market", StocksEndpoint::Crypto)
+    }
+
+    /// Returns the company profile of `symbol`.
+    pub fn company(&self, symbol: &str) -> Result<Company> {
+        self.typed_stocks_request("Company", symbol, StocksEndpoint::Company)
+    }
+
+    /// Returns the 15 minute delayed market quote of `symbol`.
+    pub fn delayed_quote(&self, symbol: &str) -> Result<DelayedQuote> {
+        self.typed_stocks_request("DelayedQuote", symbol, StocksEndpoint::DelayedQuote)
+    }
+
+    /// Returns the dividends of `symbol` paid over `duration`.
+    pub fn dividends(&self, symbol: &str, duration: Duration) -> Result<Vec<Dividend>> {
+        self.typed_stocks_request("Dividends", symbol, StocksEndpoint::Dividends { duration })
+    }
+
+    /// Returns the earnings of `symbol` for the last four quarters.
+    pub fn earnings(&self, symbol: &str) -> Result<Earnings> {
+        self.typed_stocks_request("Earnings", symbol, StocksEndpoint::Earnings)
+    }
+
+    /// Returns the effective spread of `symbol` per eligible venue.
+    pub fn effective_spread(&self, symbol: &str) -> Result<Vec<EffectiveSpread>> {
+        self.typed_stocks_request("EffectiveSpread", symbol, StocksEndpoint::EffectiveSpread)
+    }
+
+    /// Returns the income statement, balance sheet and cash flow data of
+    /// `symbol` for the last four quarters.
+    pub fn financials(&self, symbol: &str) -> Result<Financials> {
+        self.typed_stocks_request("Financials", symbol, StocksEndpoint::Financials)
+    }
+
+    /// Returns the logo of `symbol`.
+    pub fn logo(&self, symbol: &str) -> Result<Logo> {
+        self.typed_stocks_request("Logo", symbol, StocksEndpoint::Logo)
+    }
+
+    /// Returns the latest news of `symbol`; `range` is the number of items,
+    /// 10 by default.
+    pub fn news(&self, symbol: &str, range: Option<NewsCount>) -> Result<Vec<News>> {
+        self.typed_stocks_request("News", symbol, StocksEndpoint::News { range })
+    }
+
+    /// Returns the `last` news items of each of `symbols`, newest first,
+    /// fetched concurrently, a few at a time, and keyed by uppercase symbol. A story related
+    /// to several of the symbols is kept only under the first of them, in
+    /// the order of `symbols`, so that a merged feed shows it once.
+    pub fn news_multi(&self, symbols: &[&str], last: u32) -> Result<HashMap<String, Vec<News>>> {
+        let range = Some(NewsCount::new(last)?);
+        let symbols = normalize_symbols(symbols);
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        let results = map_concurrent(&symbols, MAX_CONCURRENT_NEWS, |symbol| {
+            self.news(symbol, range)
+        });
+
+        let mut seen = HashSet::new();
+        let mut news = HashMap::with_capacity(symbols.len());
+        for (symbol, result) in symbols.into_iter().zip(results) {
+            let mut items = result?;
+            items.retain(|item| seen.insert(item.headline.clone()));
+            items.sort_by_key(|item| DateTime::parse_from_rfc3339(&item.datetime).ok());
+            items.reverse();
+            news.insert(symbol.to_string(), items);
+        }
+        Ok(news)
+    }
+
+    /// Returns the official open and close of `symbol`.
+    pub fn ohlc(&self, symbol: &str) -> Result<OHLC> {
+        self.typed_stocks_request("OHLC", symbol, StocksEndpoint::Ohlc)
+    }
+
+    /// Returns the symbols of the peers of `symbol`.
+    pub fn peers(&self, symbol: &str) -> Result<Vec<String>> {
+        self.typed_stocks_request("Peers", symbol, StocksEndpoint::Peers)
+    }
+
+    /// Returns the previous day adjusted price data of `symbol`.
+    pub fn previous(&self, symbol: &str) -> Result<Previous> {
+        self.typed_stocks_request("Previous", symbol, StocksEndpoint::Previous)
+    }
+
+    /// Returns the latest price of `symbol`.
+    pub fn price(&self, symbol: &str) -> Result<f64> {
+        self.typed_stocks_request("Price", symbol, StocksEndpoint::Price)
+    }
+
+    /// Returns the quote of `symbol`. All fields are fetched; see
+    /// `quote_fields` to fetch only some of them.
+    pub fn quote(&self, symbol: &str) -> Result<Quote> {
+        if !self.display_percent() {
+            return self.typed_stocks_request("Quote", symbol, StocksEndpoint::Quote);
+        }
+        let opts = RequestOptions {
+            display_percent: Some(true),
+            ..Default::default()
+        };
+        let mut quote: Quote = self
+            .stocks_request_with(symbol, StocksEndpoint::Quote, &opts)?
+            .try_into_with(self.strictness())
+            .with_context(|e| format!("failed to deserialize Quote for {}: {}", symbol, e))
+            .map_err(MyError::from)?;
+        quote.mark_display_percent();
+        Ok(quote)
+    }
+
+    /// Returns only the `fields` of the quote of `symbol`, e.g.
+    /// `["latestPrice"]`, using the `filter` parameter to save bandwidth.
+    /// The partial quote is returned as JSON since it can't be deserialized
+    /// into a `Quote`.
+    pub fn quote_fields(&self, symbol: &str, fields: &[&str]) -> Result<Value> {
+        let opts = RequestOptions {
+            filter: fields.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        };
+        Ok(self
+            .stocks_request_with(symbol, StocksEndpoint::Quote, &opts)?
+            .0)
+    }
+
+    /// Returns symbols similar to `symbol`.
+    pub fn relevant(&self, symbol: &str) -> Result<Relevant> {
+        self.typed_stocks_request("Relevant", symbol, StocksEndpoint::Relevant)
+    }
+
+    /// Returns the splits of `symbol` over `duration`.
+    pub fn splits(&self, symbol: &str, duration: Duration) -> Result<Vec<Split>> {
+        self.typed_stocks_request("Splits", symbol, StocksEndpoint::Splits { duration })
+    }
+
+    /// Returns the key stats of `symbol`.
+    pub fn stats(&self, symbol: &str) -> Result<Stats> {
+        self.typed_stocks_request("Stats", symbol, StocksEndpoint::Stats)
+    }
+
+    /// Returns the 15 minute delayed and 30 day average volume of `symbol` per venue.
+    pub fn volume_by_venue(&self, symbol: &str) -> Result<Vec<VolumeByVenue>> {
+        self.typed_stocks_request("VolumeByVenue", symbol, StocksEndpoint::VolumeByVenue)
+    }
+}
+
+/// Sets `adjusted_close` of every point of `chart`, sorted by date, to its
+/// close back-adjusted for `splits` and `dividends`.
+///
+/// Closes before the ex-date of a split are multiplied by `for_factor /
+/// to_factor`. Closes before the ex-date of a dividend are multiplied by
+/// `1 - amount / close`, `close` being the last close before the ex-date.
+/// The factors of all later events compound.
+pub fn adjust_closes(chart: &mut [ChartDataPoint], splits: &[Split], dividends: &[Dividend]) {
+    let mut factors: Vec<(&str, f64)> = splits
+        .iter()
+        .filter(|s| s.to_factor > 0.0)
+        .map(|s| (s.ex_date.as_str(), s.for_factor / s.to_factor))
+        .collect();
+    for d in dividends {
+        let previous = chart.iter().rev().find(|p| p.date < d.ex_date);
+        if let Some(p) = previous.filter(|p| p.close > 0.0) {
+            factors.push((d.ex_date.as_str(), 1.0 - money_to_f64(d.amount) / p.close));
+        }
+    }
+
+    for point in chart.iter_mut() {
+        let factor: f64 = factors
+            .iter()
+            .filter(|(ex_date, _)| point.date.as_str() < *ex_date)
+            .map(|(_, f)| f)
+            .product();
+        point.adjusted_close = Some(point.close * factor);
+    }
+}
+
+/// Deserializes a chart from the response `body` straight into its points,
+/// without building a `Value` of the whole document first, which matters for
+/// multi-year charts of thousands of points. A `null` chart is treated as
+/// empty.
+pub fn parse_chart(body: &str) -> Result<Vec<ChartDataPoint>> {
+    let chart: Option<Vec<ChartDataPoint>> = serde_json::from_str(body).map_err(IexError::from)?;
+    Ok(chart.unwrap_or_default())
+}
+
 // pub enum ReferenceEndpoint<'a> {
 //     Symbols,
 //     CorporateActions { date: Option<&'a str> },
@@ -252,3 +888,764 @@ impl ToString for ChartParam {
 //         }
 //     }
 // }
+
+#[cfg(all(test, feature = "blocking"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::QUOTE;
+    use crate::{ClientBuilder, MockTransport, Strictness, SymbolLookup, IEX_ENDPOINT};
+    use chrono::NaiveDate;
+    use std::sync::Arc;
+
+    #[test]
+    fn quote() {
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let client = Client::with_transport(MockTransport::new().with(&url, QUOTE));
+
+        let quote = client.quote("aapl").unwrap();
+        assert_eq!(quote.symbol, "AAPL");
+    }
+
+    #[test]
+    fn quote_deserialization_error_context() {
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let mut json_data: serde_json::Value = serde_json::from_str(QUOTE).unwrap();
+        json_data.as_object_mut().unwrap().remove("latestPrice");
+        let client =
+            Client::with_transport(MockTransport::new().with(&url, &json_data.to_string()));
+
+        let err = client.quote("aapl").unwrap_err().to_string();
+        assert!(
+            err.starts_with("failed to deserialize Quote for aapl:"),
+            "{}",
+            err
+        );
+        assert!(err.contains("latestPrice"), "{}", err);
+    }
+
+    #[test]
+    fn quote_fields() {
+        let url = format!(
+            "{}/stock/aapl/quote?filter=symbol,latestPrice",
+            IEX_ENDPOINT
+        );
+        let json_data = r#"{"symbol":"AAPL","latestPrice":222.73}"#;
+        let transport = Arc::new(MockTransport::new().with(&url, json_data));
+        let client = Client::with_transport(transport.clone());
+
+        let quote = client
+            .quote_fields("aapl", &["symbol", "latestPrice"])
+            .unwrap();
+        assert_eq!(quote["latestPrice"], 222.73);
+        assert_eq!(transport.requests()[0].url, url);
+    }
+
+    #[test]
+    fn list() {
+        let quotes = format!("[{}]", QUOTE);
+        let transport = MockTransport::new()
+            .with(
+                &format!("{}/stock/market/list/mostactive", IEX_ENDPOINT),
+                &quotes,
+            )
+            .with(
+                &format!("{}/stock/market/list/iexvolume", IEX_ENDPOINT),
+                "[]",
+            )
+            .with(
+                &format!("{}/stock/market/list/infocus?listLimit=1", IEX_ENDPOINT),
+                &quotes,
+            );
+        let client = Client::with_transport(transport);
+
+        let active = client.list(ListParam::MostActive, None).unwrap();
+        assert_eq!(active[0].symbol, "AAPL");
+        assert!(client.list(ListParam::IexVolume, None).unwrap().is_empty());
+
+        let params = ListParams {
+            limit: Some(1),
+            ..Default::default()
+        };
+        let in_focus = client.list(ListParam::InFocus, Some(params)).unwrap();
+        assert_eq!(in_focus.len(), 1);
+    }
+
+    #[test]
+    fn news_multi() {
+        let item = |datetime: &str, headline: &str, related: &str| {
+            format!(
+                r#"{{"datetime":"{}","headline":"{}","source":"IEX","url":"",
+                    "summary":"","related":"{}"}}"#,
+                datetime, headline, related
+            )
+        };
+        let shared = item("2018-06-15T10:00:00-04:00", "Apple and Facebook", "AAPL,FB");
+        let aapl = format!(
+            "[{},{}]",
+            item("2018-06-14T09:00:00-04:00", "Apple earnings", "AAPL"),
+            shared
+        );
+        let fb = format!(
+            "[{},{}]",
+            shared,
+            item("2018-06-16T11:00:00-04:00", "Facebook outage", "FB")
+        );
+        let transport = MockTransport::new()
+            .with(&format!("{}/stock/AAPL/news/last/2", IEX_ENDPOINT), &aapl)
+            .with(&format!("{}/stock/FB/news/last/2", IEX_ENDPOINT), &fb);
+        let client = Client::with_transport(transport);
+
+        let news = client.news_multi(&["aapl", "fb", "AAPL"], 2).unwrap();
+        assert_eq!(news.len(), 2);
+        let headlines = |symbol: &str| -> Vec<String> {
+            news.get_symbol(symbol)
+                .unwrap()
+                .iter()
+                .map(|n| n.headline.clone())
+                .collect()
+        };
+        assert_eq!(headlines("aapl"), ["Apple and Facebook", "Apple earnings"]);
+        assert_eq!(headlines("fb"), ["Facebook outage"]);
+        assert!(client.news_multi(&["aapl"], 0).is_err());
+    }
+
+    #[test]
+    fn crypto_list() {
+        let pair = |symbol: &str, price: &str| {
+            format!(
+                r#"{{"symbol":"{}","companyName":"","primaryExchange":"crypto",
+                    "sector":"cryptocurrency","calculationPrice":"realtime",
+                    "latestPrice":{},"latestSource":"Real time price",
+                    "latestTime":"10:32:43 AM","latestUpdate":1537799563424,
+                    "previousClose":null,"change":0,"changePercent":0,
+                    "bidPrice":0,"bidSize":0,"askPrice":0,"askSize":0}}"#,
+                symbol, price
+            )
+        };
+        let json_data = format!(
+            "[{},{}]",
+            pair("BTCUSDT", "6441.78342"),
+            pair("ETHUSDT", "228.14")
+        );
+        let url = format!("{}/stock/market/crypto", IEX_ENDPOINT);
+        let client = Client::with_transport(MockTransport::new().with(&url, &json_data));
+
+        let quotes = client.crypto_list().unwrap();
+        let symbols: Vec<&str> = quotes.iter().map(|q| q.symbol.as_str()).collect();
+        assert_eq!(symbols, ["BTCUSDT", "ETHUSDT"]);
+        assert_eq!(quotes[0].latest_price, crate::fixtures::money("6441.78342"));
+    }
+
+    #[test]
+    fn display_percent() {
+        let displayed = QUOTE.replace("0.00555", "0.555");
+        let transport = MockTransport::new()
+            .with(&format!("{}/stock/aapl/quote", IEX_ENDPOINT), QUOTE)
+            .with(
+                &format!("{}/stock/aapl/quote?displayPercent=true", IEX_ENDPOINT),
+                &displayed,
+            )
+            .with(
+                &format!(
+                    "{}/stock/market/list/gainers?displayPercent=true",
+                    IEX_ENDPOINT
+                ),
+                &format!("[{}]", displayed),
+            );
+        let transport = Arc::new(transport);
+
+        let ratio = Client::with_transport(transport.clone())
+            .quote("aapl")
+            .unwrap();
+        assert!(!ratio.change_percent.is_display());
+        assert_eq!(ratio.change_percent.value(), 0.00555);
+
+        let client = crate::ClientBuilder::new()
+            .transport(transport)
+            .display_percent(true)
+            .build();
+        let quote = client.quote("aapl").unwrap();
+        assert!(quote.change_percent.is_display());
+        assert_eq!(quote.change_percent.value(), 0.555);
+        assert!((quote.change_percent.ratio() - 0.00555).abs() < 1e-12);
+
+        let saved = serde_json::to_string(&quote).unwrap();
+        let reloaded: Quote = serde_json::from_str(&saved).unwrap();
+        assert!(!reloaded.change_percent.is_display());
+        assert!((reloaded.change_percent.ratio() - 0.00555).abs() < 1e-12);
+        assert!((reloaded.change_percent.percent() - 0.555).abs() < 1e-12);
+
+        let gainers = client.list(ListParam::Gainers, None).unwrap();
+        assert_eq!(gainers[0].change_percent.percent(), 0.555);
+    }
+
+    #[test]
+    fn fetch_typed_returns_value_and_response() {
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let client = Client::with_transport(MockTransport::new().with(&url, QUOTE));
+
+        let (quote, response): (Quote, Response) =
+            client.fetch_typed("aapl", StocksEndpoint::Quote).unwrap();
+        assert_eq!(quote.symbol, "AAPL");
+        assert_eq!(response.0["symbol"], "AAPL");
+        assert_eq!(
+            response.0,
+            serde_json::from_str::<serde_json::Value>(QUOTE).unwrap()
+        );
+    }
+
+    #[test]
+    fn fetch_typed_respects_strictness() {
+        let url = format!("{}/stock/aapl/logo", IEX_ENDPOINT);
+        let transport = Arc::new(MockTransport::new().with(
+            &url,
+            r#"{"url":"https://example.com/aapl.png","format":"png"}"#,
+        ));
+        let client = |strictness| {
+            ClientBuilder::new()
+                .transport(transport.clone())
+                .strictness(strictness)
+                .build()
+        };
+
+        let fetch = |client: Client| client.fetch_typed::<Logo>("aapl", StocksEndpoint::Logo);
+        assert!(fetch(client(Strictness::Lenient)).is_ok());
+        let err = fetch(client(Strictness::Strict)).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `format`"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn earnings_today() {
+        let json_data = r#"{
+            "bto": [{
+                "actualEPS": 2.1,
+                "consensusEPS": 2.02,
+                "estimatedEPS": 2.02,
+                "announceTime": "BTO",
+                "numberOfEstimates": 14,
+                "EPSSurpriseDollar": 0.08,
+                "EPSReportDate": "2017-05-02",
+                "fiscalPeriod": "Q2 2017",
+                "fiscalEndDate": "2017-03-31",
+                "yearAgo": 1.67,
+                "symbol": "AAPL",
+                "headline": ""
+            }],
+            "amc": [{
+                "actualEPS": null,
+                "consensusEPS": 0.78,
+                "estimatedEPS": 0.78,
+                "announceTime": "AMC",
+                "EPSReportDate": "2017-05-02",
+                "fiscalPeriod": "Q1 2017",
+                "fiscalEndDate": "2017-03-31",
+                "symbol": "FB"
+            }]
+        }"#;
+        let url = format!("{}/stock/market/today-earnings", IEX_ENDPOINT);
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let earnings = client.earnings_today().unwrap();
+        assert_eq!(earnings.before_open.len(), 1);
+        assert_eq!(earnings.before_open[0].symbol, "AAPL");
+        assert_eq!(earnings.before_open[0].consensus_eps, Some(2.02));
+        let fb = &earnings.after_close[0];
+        assert_eq!(fb.symbol, "FB");
+        assert_eq!(fb.actual_eps, None);
+        assert_eq!(fb.number_of_estimates, None);
+    }
+
+    #[test]
+    fn upcoming_ipos() {
+        let json_data = r#"{
+            "rawData": [{
+                "symbol": "VCNX",
+                "companyName": "VACCINEX, INC.",
+                "expectedDate": "2018-08-09",
+                "leadUnderwriters": ["BTIG, LLC", "Oppenheimer & Co. Inc."],
+                "market": "NASDAQ Global",
+                "status": "Filed",
+                "sharesOffered": 3333000,
+                "priceLow": 12,
+                "priceHigh": 15,
+                "offerAmount": null,
+                "amount": 44995500,
+                "percentOffered": "29.05"
+            }],
+            "viewData": [{
+                "Company": "VACCINEX, INC.",
+                "Symbol": "VCNX",
+                "Price": "$12.00 - 15.00",
+                "Shares": "3,333,000",
+                "Expected": "2018-08-09"
+            }]
+        }"#;
+        let url = format!("{}/stock/market/upcoming-ipos", IEX_ENDPOINT);
+        let client = Client::with_transport(MockTransport::new().with(&url, json_data));
+
+        let ipos = client.upcoming_ipos().unwrap();
+        assert_eq!(ipos.len(), 1);
+        let ipo = &ipos[0];
+        assert_eq!(ipo.symbol, "VCNX");
+        assert_eq!(ipo.company_name, "VACCINEX, INC.");
+        assert_eq!(
+            ipo.expected_date,
+            NaiveDate::from_ymd_opt(2018, 8, 9).unwrap()
+        );
+        assert_eq!(ipo.price_low, Some(12.0));
+        assert_eq!(ipo.price_high, Some(15.0));
+        assert_eq!(ipo.offer_amount, None);
+        assert_eq!(ipo.shares, Some(3333000));
+        assert_eq!(ipo.status, "Filed");
+    }
+
+    #[test]
+    fn parse_large_chart() {
+        let points: Vec<String> = (0..5000)
+            .map(|i| chart_point("2010-01-04", 100.0 + f64::from(i)))
+            .collect();
+        let body = format!("[{}]", points.join(","));
+
+        let chart = parse_chart(&body).unwrap();
+        assert_eq!(chart.len(), 5000);
+        assert_eq!(chart[4999].close, 5099.0);
+        assert!(parse_chart("null").unwrap().is_empty());
+        assert!(parse_chart("[] trailing").is_err());
+    }
+
+    #[test]
+    fn chart_five_years() {
+        let url = format!("{}/stock/aapl/chart/5y", IEX_ENDPOINT);
+        let body = format!(
+            "[{},{}]",
+            chart_point("2010-01-04", 1.0),
+            chart_point("2010-01-05", 2.0)
+        );
+        let client = Client::with_transport(MockTransport::new().with(&url, &body));
+
+        let chart = client.chart("aapl", Duration::FiveYears, None).unwrap();
+        assert_eq!(chart.len(), 2);
+    }
+
+    #[test]
+    fn chart_last() {
+        let points = |n: usize| {
+            let points: Vec<String> = (0..n)
+                .map(|i| chart_point("2018-01-29", i as f64))
+                .collect();
+            format!("[{}]", points.join(","))
+        };
+        let url = format!("{}/stock/aapl/chart/1y?chartLast=2", IEX_ENDPOINT);
+        let by_date = format!("{}/stock/aapl/chart/date/20180129", IEX_ENDPOINT);
+        let transport = Arc::new(
+            MockTransport::new()
+                .with(&url, &points(2))
+                .with(&by_date, &points(5)),
+        );
+        let client = Client::with_transport(transport.clone());
+
+        let chart = client.chart_last("aapl", Duration::OneYear, 2).unwrap();
+        assert_eq!(chart.len(), 2);
+        assert_eq!(transport.requests()[0].url, url);
+
+        let chart = client
+            .chart_last("aapl", Duration::Date("20180129"), 2)
+            .unwrap();
+        let closes: Vec<f64> = chart.iter().map(|p| p.close).collect();
+        assert_eq!(closes, [3.0, 4.0]);
+    }
+
+    #[test]
+    fn intraday_chart_on() {
+        let bars = r#"[
+            {"date": "20180129", "minute": "09:30", "label": "09:30 AM",
+             "high": 143.98, "low": 143.775, "open": 143.98, "close": 143.775,
+             "average": 143.889, "volume": 3070, "notional": 441740.275,
+             "numberOfTrades": 20, "changeOverTime": -0.0039}
+        ]"#;
+        let minutes = format!("{}/stock/aapl/chart/date/20180129", IEX_ENDPOINT);
+        let by_day = format!(
+            "{}/stock/aapl/chart/date/20180129?chartByDay=true",
+            IEX_ENDPOINT
+        );
+        let transport = MockTransport::new()
+            .with(&minutes, bars)
+            .with(&by_day, &format!("[{}]", chart_point("2018-01-29", 167.96)));
+        let client = Client::with_transport(transport);
+        let date = NaiveDate::from_ymd_opt(2018, 1, 29).unwrap();
+
+        let bars = client.intraday_chart_on("aapl", date).unwrap();
+        assert_eq!(bars.len(), 1);
+        assert!(bars[0].is_regular_hours());
+
+        let day = client.day_chart_on("aapl", date).unwrap().unwrap();
+        assert_eq!(day.date, "2018-01-29");
+        assert_eq!(day.close, 167.96);
+    }
+
+    fn chart_point(date: &str, close: f64) -> String {
+        format!(
+            r#"{{"date":"{}","open":{c},"high":{c},"low":{c},"close":{c},"volume":1000,
+                "unadjustedVolume":1000,"change":0,"changePercent":0,"vwap":{c},
+                "label":"","changeOverTime":0}}"#,
+            date,
+            c = close
+        )
+    }
+
+    #[test]
+    fn adjusted_chart_split() {
+        let chart = format!(
+            "[{},{},{}]",
+            chart_point("2014-06-05", 647.35),
+            chart_point("2014-06-06", 645.57),
+            chart_point("2014-06-09", 93.70)
+        );
+        let splits = r#"[{"exDate":"2014-06-09","declaredDate":"2014-04-23","recordDate":"2014-06-02",
+            "paymentDate":"2014-06-06","ratio":0.142857,"toFactor":7,"forFactor":1}]"#;
+        let transport = MockTransport::new()
+            .with(&format!("{}/stock/aapl/chart/1y", IEX_ENDPOINT), &chart)
+            .with(&format!("{}/stock/aapl/splits/1y", IEX_ENDPOINT), splits)
+            .with(&format!("{}/stock/aapl/dividends/1y", IEX_ENDPOINT), "[]");
+        let client = Client::with_transport(transport);
+
+        let chart = client.adjusted_chart("aapl", Duration::OneYear).unwrap();
+        let closes: Vec<f64> = chart.iter().map(|p| p.adjusted_close.unwrap()).collect();
+        assert!((closes[0] - 647.35 / 7.0).abs() < 1e-9);
+        assert!((closes[1] - 645.57 / 7.0).abs() < 1e-9);
+        assert_eq!(closes[2], 93.70);
+    }
+
+    #[test]
+    fn adjust_closes_dividend() {
+        let json_data = format!(
+            "[{},{},{}]",
+            chart_point("2018-08-08", 100.0),
+            chart_point("2018-08-09", 100.0),
+            chart_point("2018-08-10", 99.0)
+        );
+        let mut chart: Vec<ChartDataPoint> = serde_json::from_str(&json_data).unwrap();
+        let dividends: Vec<Dividend> = serde_json::from_str(
+            r#"[{"exDate":"2018-08-10","paymentDate":"2018-08-16","recordDate":"2018-08-13",
+                "declaredDate":"2018-07-31","amount":1.0,"flag":"","type":"Dividend income",
+                "qualified":"Q","indicated":""}]"#,
+        )
+        .unwrap();
+
+        adjust_closes(&mut chart, &[], &dividends);
+        let closes: Vec<f64> = chart.iter().map(|p| p.adjusted_close.unwrap()).collect();
+        assert!((closes[0] - 99.0).abs() < 1e-9);
+        assert!((closes[1] - 99.0).abs() < 1e-9);
+        assert_eq!(closes[2], 99.0);
+    }
+
+    #[test]
+    fn endpoint_tokens() {
+        let all = StocksEndpoint::all_variants();
+        for endpoint in SIMPLE_ENDPOINTS.iter() {
+            let token = endpoint.token();
+            assert!(all.contains(&token), "{}", token);
+            assert_eq!(StocksEndpoint::from_token(token), Some(*endpoint));
+        }
+        assert_eq!(StocksEndpoint::from_token("chart"), None);
+        assert_eq!(StocksEndpoint::from_token("bogus"), None);
+    }
+
+    /// Returns every variant of `StocksEndpoint`, with and without its
+    /// optional parameters. The match has no wildcard arm, so adding a
+    /// variant doesn't compile until it is listed here too.
+    fn every_endpoint() -> Vec<StocksEndpoint<'static>> {
+        let chart_params = ChartParams {
+            reset: Some(true),
+            last: Some(5),
+            ..Default::default()
+        };
+        let list_params = ListParams {
+            display_percent: Some(true),
+            limit: Some(5),
+        };
+        let mut endpoints = SIMPLE_ENDPOINTS.to_vec();
+        endpoints.extend(vec![
+            StocksEndpoint::Chart {
+                duration: Duration::OneDay,
+                params: None,
+            },
+            StocksEndpoint::Chart {
+                duration: Duration::Date("20180129"),
+                params: Some(chart_params),
+            },
+            StocksEndpoint::Dividends {
+                duration: Duration::FiveYears,
+            },
+            StocksEndpoint::List {
+                param: ListParam::Gainers,
+                params: None,
+            },
+            StocksEndpoint::List {
+                param: ListParam::InFocus,
+                params: Some(list_params),
+            },
+            StocksEndpoint::News { range: None },
+            StocksEndpoint::News {
+                range: Some(NewsCount::clamped(5)),
+            },
+            StocksEndpoint::Splits {
+                duration: Duration::OneYear,
+            },
+            StocksEndpoint::ThresholdSecurities { date: None },
+            StocksEndpoint::ThresholdSecurities {
+                date: Some(Duration::Date("20180129")),
+            },
+        ]);
+        for endpoint in &endpoints {
+            match endpoint {
+                StocksEndpoint::Book
+                | StocksEndpoint::Chart { .. }
+                | StocksEndpoint::Company
+                | StocksEndpoint::Crypto
+                | StocksEndpoint::DelayedQuote
+                | StocksEndpoint::Dividends { .. }
+                | StocksEndpoint::Earnings
+                | StocksEndpoint::EffectiveSpread
+                | StocksEndpoint::Financials
+                | StocksEndpoint::List { .. }
+                | StocksEndpoint::Logo
+                | StocksEndpoint::News { .. }
+                | StocksEndpoint::Ohlc
+                | StocksEndpoint::Peers
+                | StocksEndpoint::Previous
+                | StocksEndpoint::Price
+                | StocksEndpoint::Quote
+                | StocksEndpoint::Relevant
+                | StocksEndpoint::Splits { .. }
+                | StocksEndpoint::Stats
+                | StocksEndpoint::TodayEarnings
+                | StocksEndpoint::TodayIpos
+                | StocksEndpoint::UpcomingIpos
+                | StocksEndpoint::TimeSeries
+                | StocksEndpoint::ThresholdSecurities { .. }
+                | StocksEndpoint::VolumeByVenue => {}
+            }
+        }
+        endpoints
+    }
+
+    #[test]
+    fn every_endpoint_is_well_formed() {
+        let endpoints = every_endpoint();
+        let mut tokens: Vec<&str> = endpoints.iter().map(|e| e.token()).collect();
+        tokens.sort_unstable();
+        tokens.dedup();
+        let mut all = StocksEndpoint::all_variants();
+        all.sort_unstable();
+        assert_eq!(tokens, all);
+
+        for endpoint in endpoints {
+            let path = endpoint.to_endpoint();
+            let (segments, query) = match path.find('?') {
+                Some(i) => (&path[..i], Some(&path[i + 1..])),
+                None => (path.as_str(), None),
+            };
+            assert!(segments.starts_with(endpoint.token()), "{}", path);
+            assert!(!segments.ends_with('/'), "{}", path);
+            assert!(segments.split('/').all(|s| !s.is_empty()), "{}", path);
+            if let Some(query) = query {
+                assert!(!query.is_empty(), "{}", path);
+                assert!(!query.contains('?'), "{}", path);
+                for pair in query.split('&') {
+                    let mut kv = pair.splitn(2, '=');
+                    assert!(!kv.next().unwrap().is_empty(), "{}", path);
+                    assert!(!kv.next().unwrap_or_default().is_empty(), "{}", path);
+                }
+            }
+        }
+    }
+
+    fn url(endpoint: StocksEndpoint) -> String {
+        build_stock_url("https://iex", "aapl", &endpoint, &RequestOptions::default())
+    }
+
+    #[test]
+    fn stock_urls() {
+        let cases = vec![
+            (StocksEndpoint::Book, "book"),
+            (
+                StocksEndpoint::Chart {
+                    duration: Duration::FiveYears,
+                    params: None,
+                },
+                "chart/5y",
+            ),
+            (
+                StocksEndpoint::Chart {
+                    duration: Duration::Date("20180129"),
+                    params: Some(ChartParams {
+                        last: Some(10),
+                        ..Default::default()
+                    }),
+                },
+                "chart/date/20180129?chartLast=10",
+            ),
+            (StocksEndpoint::Company, "company"),
+            (StocksEndpoint::DelayedQuote, "delayed-quote"),
+            (
+                StocksEndpoint::Dividends {
+                    duration: Duration::YearToDate,
+                },
+                "dividends/ytd",
+            ),
+            (StocksEndpoint::Earnings, "earnings"),
+            (StocksEndpoint::EffectiveSpread, "effective-spread"),
+            (StocksEndpoint::Financials, "financials"),
+            (
+                StocksEndpoint::List {
+                    param: ListParam::MostActive,
+                    params: None,
+                },
+                "list/mostactive",
+            ),
+            (
+                StocksEndpoint::List {
+                    param: ListParam::InFocus,
+                    params: Some(ListParams {
+                        display_percent: Some(true),
+                        limit: Some(25),
+                    }),
+                },
+                "list/infocus?displayPercent=true&listLimit=25",
+            ),
+            (StocksEndpoint::Logo, "logo"),
+            (StocksEndpoint::News { range: None }, "news"),
+            (
+                StocksEndpoint::News {
+                    range: Some(NewsCount::clamped(3)),
+                },
+                "news/last/3",
+            ),
+            (StocksEndpoint::Ohlc, "ohlc"),
+            (StocksEndpoint::Peers, "peers"),
+            (StocksEndpoint::Previous, "previous"),
+            (StocksEndpoint::Price, "price"),
+            (StocksEndpoint::Quote, "quote"),
+            (StocksEndpoint::Relevant, "relevant"),
+            (
+                StocksEndpoint::Splits {
+                    duration: Duration::TwoYears,
+                },
+                "splits/2y",
+            ),
+            (StocksEndpoint::Stats, "stats"),
+            (StocksEndpoint::TimeSeries, "time-series"),
+            (
+                StocksEndpoint::ThresholdSecurities { date: None },
+                "threshold-securities",
+            ),
+            (
+                StocksEndpoint::ThresholdSecurities {
+                    date: Some(Duration::Date("20180129")),
+                },
+                "threshold-securities/date/20180129",
+            ),
+            (StocksEndpoint::VolumeByVenue, "volume-by-venue"),
+        ];
+        for (endpoint, path) in cases {
+            assert_eq!(url(endpoint), format!("https://iex/stock/aapl/{}", path));
+        }
+    }
+
+    #[test]
+    fn stock_url_with_options() {
+        let opts = RequestOptions {
+            filter: vec!["symbol".to_string(), "latestPrice".to_string()],
+            token: Some("pk_test".to_string()),
+            format: Some("csv".to_string()),
+            display_percent: Some(true),
+        };
+        assert_eq!(
+            build_stock_url("https://iex", "aapl", &StocksEndpoint::Quote, &opts),
+            "https://iex/stock/aapl/quote?filter=symbol,latestPrice&token=pk_test&format=csv\
+             &displayPercent=true"
+        );
+
+        let endpoint = StocksEndpoint::Chart {
+            duration: Duration::OneDay,
+            params: Some(ChartParams {
+                interval: Some(5),
+                ..Default::default()
+            }),
+        };
+        let opts = RequestOptions {
+            filter: vec!["close".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            build_stock_url("https://iex", "aapl", &endpoint, &opts),
+            "https://iex/stock/aapl/chart/1d?chartInterval=5&filter=close"
+        );
+    }
+
+    #[test]
+    fn news_count() {
+        assert_eq!(NewsCount::new(1).unwrap().get(), 1);
+        assert_eq!(NewsCount::new(50).unwrap().get(), 50);
+        assert!(NewsCount::new(0).is_err());
+        assert!(NewsCount::new(51).is_err());
+        assert_eq!(NewsCount::clamped(0).get(), 1);
+        assert_eq!(NewsCount::clamped(100).get(), 50);
+    }
+
+    #[test]
+    fn news_endpoint() {
+        let endpoint = StocksEndpoint::News {
+            range: Some(NewsCount::new(5).unwrap()),
+        };
+        assert_eq!(endpoint.to_endpoint(), "news/last/5");
+        assert_eq!(StocksEndpoint::News { range: None }.to_endpoint(), "news");
+    }
+
+    #[test]
+    fn chart_params_query() {
+        let params = ChartParams {
+            reset: Some(true),
+            simplify: Some(false),
+            interval: Some(5),
+            change_from_close: Some(true),
+            last: Some(10),
+            by_day: Some(true),
+        };
+        assert_eq!(
+            params.to_query(),
+            "chartReset=true&chartSimplify=false&chartInterval=5&changeFromClose=true&chartLast=10\
+             &chartByDay=true"
+        );
+    }
+
+    #[test]
+    fn chart_endpoint_with_params() {
+        let endpoint = StocksEndpoint::Chart {
+            duration: Duration::OneDay,
+            params: Some(ChartParams {
+                interval: Some(5),
+                last: Some(10),
+                ..Default::default()
+            }),
+        };
+        assert_eq!(
+            endpoint.to_endpoint(),
+            "chart/1d?chartInterval=5&chartLast=10"
+        );
+    }
+
+    #[test]
+    fn chart_endpoint_without_params() {
+        let endpoint = StocksEndpoint::Chart {
+            duration: Duration::OneMonth,
+            params: None,
+        };
+        assert_eq!(endpoint.to_endpoint(), "chart/1m");
+    }
+}
@@ -0,0 +1,148 @@
+/*! Request metrics
+
+Recorded through the `metrics` crate when the `metrics` feature is enabled,
+once per request (retries included, identical concurrent requests counted
+once):
+
+* `iex_requests_total` (counter), labeled by `endpoint` and `outcome`
+  (`success` or `error`, the latter for transport errors and error
+  statuses).
+* `iex_request_duration_seconds` (histogram), labeled by `endpoint`.
+
+The `endpoint` label is the path of the URL without the version and the
+symbol, e.g. `stock/quote` or `ref-data/symbols`. Dates and counts in the
+path are replaced by `{date}` and `{n}`, e.g. `stock/chart/date/{date}` or
+`stock/news/last/{n}`, so that the label takes a bounded set of values.
+ */
+
+use super::{RawResponse, Result};
+use std::time::Duration;
+
+/// Records the metrics of a request for `url`.
+pub(crate) fn record_request(url: &str, result: &Result<RawResponse>, elapsed: Duration) {
+    let endpoint = endpoint_label(url);
+    let outcome = match result {
+        Ok(raw) if raw.status < 400 => "success",
+        _ => "error",
+    };
+    metrics::counter!("iex_requests_total", "endpoint" => endpoint.clone(), "outcome" => outcome)
+        .increment(1);
+    metrics::histogram!("iex_request_duration_seconds", "endpoint" => endpoint)
+        .record(elapsed.as_secs_f64());
+}
+
+/// Returns the `endpoint` label of `url`, see the module documentation.
+fn endpoint_label(url: &str) -> String {
+    let path = url
+        .splitn(4, '/')
+        .nth(3)
+        .unwrap_or_default()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+    let mut segments: Vec<&str> = path.split('/').skip(1).collect();
+    if segments.first() == Some(&"stock") && segments.len() > 2 {
+        segments.remove(1);
+    }
+    for segment in &mut segments {
+        if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+            *segment = if segment.len() == 8 { "{date}" } else { "{n}" };
+        }
+    }
+    segments.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_labels() {
+        let base = "https://api.iextrading.com/1.0";
+        let label = |path: &str| endpoint_label(&format!("{}{}", base, path));
+        assert_eq!(label("/stock/aapl/quote"), "stock/quote");
+        assert_eq!(label("/stock/aapl/chart/1y?chartLast=5"), "stock/chart/1y");
+        assert_eq!(label("/stock/market/list/gainers"), "stock/list/gainers");
+        assert_eq!(label("/ref-data/symbols?format=csv"), "ref-data/symbols");
+        assert_eq!(label("/market"), "market");
+        assert_eq!(
+            label("/stock/aapl/chart/date/20180129"),
+            "stock/chart/date/{date}"
+        );
+        assert_eq!(label("/stock/aapl/news/last/5"), "stock/news/last/{n}");
+        assert_eq!(
+            label("/ref-data/daily-list/corporate-actions/20171108"),
+            "ref-data/daily-list/corporate-actions/{date}"
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn request_counter() {
+        use crate::{Client, MockTransport, IEX_ENDPOINT};
+        use metrics::{Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder};
+        use metrics::{SharedString, Unit};
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+
+        type Counts = Arc<Mutex<HashMap<String, u64>>>;
+
+        struct Count(Counts, String);
+
+        impl CounterFn for Count {
+            fn increment(&self, value: u64) {
+                *self.0.lock().unwrap().entry(self.1.clone()).or_default() += value;
+            }
+
+            fn absolute(&self, value: u64) {
+                self.0.lock().unwrap().insert(self.1.clone(), value);
+            }
+        }
+
+        #[derive(Default)]
+        struct CountingRecorder(Counts);
+
+        impl Recorder for CountingRecorder {
+            fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+            fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+            fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+                let labels: Vec<String> = key
+                    .labels()
+                    .map(|l| format!("{}={}", l.key(), l.value()))
+                    .collect();
+                let name = format!("{}{{{}}}", key.name(), labels.join(","));
+                Counter::from_arc(Arc::new(Count(self.0.clone(), name)))
+            }
+
+            fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+                Gauge::noop()
+            }
+
+            fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+                Histogram::noop()
+            }
+        }
+
+        let url = format!("{}/stock/aapl/quote", IEX_ENDPOINT);
+        let client =
+            Client::with_transport(MockTransport::new().with(&url, crate::fixtures::QUOTE));
+        let recorder = CountingRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            client.quote("aapl").unwrap();
+            client.quote("aapl").unwrap();
+            let _ = client.quote("zzzz");
+        });
+
+        let counts = recorder.0.lock().unwrap();
+        assert_eq!(
+            counts["iex_requests_total{endpoint=stock/quote,outcome=success}"],
+            2
+        );
+        assert_eq!(
+            counts["iex_requests_total{endpoint=stock/quote,outcome=error}"],
+            1
+        );
+    }
+}
@@ -1,10 +1,20 @@
 /*! API Object types.
  */
 
+use super::{
+    from_collapsed_str, from_millis, from_money, from_opt_money, money_to_f64, to_millis, to_money,
+    to_opt_money, Money,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_aux::prelude::deserialize_number_from_string;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+
 pub type Tops = Vec<TopsData>;
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct TopsData {
     pub sector: String,
     pub security_type: String,
@@ -19,65 +29,315 @@ pub struct TopsData {
     pub market_percent: f64,
 }
 
-#[serde(rename_all = "camelCase")]
+/// Percentage sent by IEX. IEX sends ratios, e.g. `0.0123` for 1.23%,
+/// unless the `displayPercent` parameter is set (see
+/// `ClientBuilder::display_percent`), in which case the values are
+/// multiplied by 100. A `Percent` knows which convention its value follows,
+/// so `ratio` and `percent` are right either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Percent {
+    value: f64,
+    display: bool,
+}
+
+impl Percent {
+    /// Returns the percentage of a ratio, e.g. `0.0123` for 1.23%.
+    pub fn from_ratio(ratio: f64) -> Self {
+        Percent {
+            value: ratio,
+            display: false,
+        }
+    }
+
+    /// Returns the percentage of a value multiplied by 100, e.g. `1.23` for
+    /// 1.23%.
+    pub fn from_display(percent: f64) -> Self {
+        Percent {
+            value: percent,
+            display: true,
+        }
+    }
+
+    /// Returns the value as sent by IEX.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Returns true if the value was multiplied by 100 by IEX.
+    pub fn is_display(&self) -> bool {
+        self.display
+    }
+
+    /// Returns the percentage as a ratio, e.g. `0.0123` for 1.23%.
+    pub fn ratio(&self) -> f64 {
+        if self.display {
+            self.value / 100.0
+        } else {
+            self.value
+        }
+    }
+
+    /// Returns the percentage multiplied by 100, e.g. `1.23` for 1.23%.
+    pub fn percent(&self) -> f64 {
+        if self.display {
+            self.value
+        } else {
+            self.value * 100.0
+        }
+    }
+}
+
+/// Deserializes a ratio; the client marks the values of requests sent with
+/// `displayPercent` afterwards, see `Quote::mark_display_percent`.
+impl<'de> Deserialize<'de> for Percent {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        f64::deserialize(deserializer).map(Percent::from_ratio)
+    }
+}
+
+/// Serializes the ratio, whichever convention IEX followed, so that the
+/// value reads back right with `Deserialize`.
+impl Serialize for Percent {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(self.ratio())
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Quote {
     pub symbol: String,
     pub company_name: String,
     pub primary_exchange: String,
     pub sector: String,
     pub calculation_price: String,
-    pub open: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub open: Money,
     pub open_time: f64,
-    pub close: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub close: Money,
     pub close_time: f64,
-    pub high: Option<f64>,
-    pub low: Option<f64>,
-    pub latest_price: f64,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub high: Option<Money>,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub low: Option<Money>,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub latest_price: Money,
     pub latest_source: String,
     pub latest_time: String,
-    pub latest_update: f64,
+    /// Time the latest price was last updated.
+    #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+    pub latest_update: DateTime<Utc>,
     pub latest_volume: f64,
-    pub iex_realtime_price: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub iex_realtime_price: Money,
     pub iex_realtime_size: f64,
     pub iex_last_updated: f64,
-    pub delayed_price: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub delayed_price: Money,
     pub delayed_price_time: f64,
-    pub previous_close: f64,
-    pub change: f64,
-    pub change_percent: f64,
-    pub iex_market_percent: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub previous_close: Money,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub change: Money,
+    pub change_percent: Percent,
+    pub iex_market_percent: Percent,
     pub iex_volume: f64,
     pub avg_total_volume: f64,
-    pub iex_bid_price: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub iex_bid_price: Money,
     pub iex_bid_size: f64,
-    pub iex_ask_price: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub iex_ask_price: Money,
     pub iex_ask_size: f64,
     pub market_cap: f64,
+    #[serde(default)]
     pub pe_ratio: Option<f64>,
-    pub week52_high: f64,
-    pub week52_low: f64,
-    pub ytd_change: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub week52_high: Money,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub week52_low: Money,
+    pub ytd_change: Percent,
 }
 
-#[serde(rename_all = "camelCase")]
+impl Quote {
+    /// Marks the percentages of a quote requested with `displayPercent`,
+    /// which IEX multiplied by 100.
+    pub(crate) fn mark_display_percent(&mut self) {
+        self.change_percent.display = true;
+        self.iex_market_percent.display = true;
+        self.ytd_change.display = true;
+    }
+
+    /// Returns whether the price went up (`Greater`), down (`Less`) or stayed
+    /// unchanged (`Equal`) compared to the previous close.
+    pub fn change_direction(&self) -> Ordering {
+        self.change
+            .partial_cmp(&Money::default())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Returns how long ago, as of `now`, the latest price was updated.
+    pub fn age(&self, now: DateTime<Utc>) -> Duration {
+        now.signed_duration_since(self.latest_update)
+    }
+
+    /// Returns true if, as of `now`, the latest price was updated more than
+    /// `max_age` ago.
+    pub fn is_stale(&self, now: DateTime<Utc>, max_age: Duration) -> bool {
+        self.age(now) > max_age
+    }
+
+    /// Returns the IEX bid and ask prices, or `None` if either is zero, as
+    /// IEX reports them outside regular hours.
+    fn bid_ask(&self) -> Option<(f64, f64)> {
+        let bid = money_to_f64(self.iex_bid_price);
+        let ask = money_to_f64(self.iex_ask_price);
+        if bid > 0.0 && ask > 0.0 {
+            Some((bid, ask))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the spread between the IEX ask and bid prices, or `None` if
+    /// there is no bid or no ask, e.g. outside regular hours.
+    pub fn spread(&self) -> Option<f64> {
+        self.bid_ask().map(|(bid, ask)| ask - bid)
+    }
+
+    /// Returns the midpoint of the IEX bid and ask prices, or `None` if
+    /// there is no bid or no ask, e.g. outside regular hours.
+    pub fn mid(&self) -> Option<f64> {
+        self.bid_ask().map(|(bid, ask)| (bid + ask) / 2.0)
+    }
+
+    /// Returns where the latest price sits in the 52-week range, from 0.0 at
+    /// the low to 1.0 at the high, or `None` if the range is unknown (sent
+    /// as zeros) or empty. A price beyond the range, not yet reflected in
+    /// it, falls outside `0.0..=1.0`.
+    pub fn position_in_52w_range(&self) -> Option<f64> {
+        let low = money_to_f64(self.week52_low);
+        let high = money_to_f64(self.week52_high);
+        if high <= low || low <= 0.0 {
+            return None;
+        }
+        Some((money_to_f64(self.latest_price) - low) / (high - low))
+    }
+}
+
+/// Renders a compact one-line summary, e.g.
+/// `AAPL 222.73 +1.23 (+0.56%) vol 32112345`.
+impl Display for Quote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.2} {:+.2} ({:+.2}%) vol {:.0}",
+            self.symbol,
+            self.latest_price,
+            self.change,
+            self.change_percent.percent(),
+            self.latest_volume
+        )
+    }
+}
+
+/// Quote of a cryptocurrency pair, e.g. `BTCUSDT`, as returned by
+/// `/stock/{symbol}/quote`. Unlike stock quotes it has a bid and an ask but
+/// none of the IEX specific fields. Prices are `Money`, so the `decimal`
+/// feature keeps their full precision.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct Bid {
-    pub price: f64,
-    pub size: f64,
-    pub timestamp: f64,
+#[serde(rename_all = "camelCase")]
+pub struct CryptoQuote {
+    pub symbol: String,
+    pub company_name: String,
+    pub primary_exchange: String,
+    pub sector: String,
+    pub calculation_price: String,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub open: Option<Money>,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub close: Option<Money>,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub high: Option<Money>,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub low: Option<Money>,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub latest_price: Money,
+    pub latest_source: String,
+    pub latest_time: String,
+    /// Time the latest price was last updated.
+    #[serde(deserialize_with = "from_millis", serialize_with = "to_millis")]
+    pub latest_update: DateTime<Utc>,
+    /// Traded volume, in units of the base currency.
+    #[serde(default)]
+    pub latest_volume: Option<f64>,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub previous_close: Option<Money>,
+    #[serde(
+        default,
+        deserialize_with = "from_opt_money",
+        serialize_with = "to_opt_money"
+    )]
+    pub change: Option<Money>,
+    #[serde(default)]
+    pub change_percent: Option<f64>,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub bid_price: Money,
+    pub bid_size: f64,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub ask_price: Money,
+    pub ask_size: f64,
 }
 
+/// A price level of the IEX order book.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Ask {
+pub struct BookLevel {
     pub price: f64,
     pub size: f64,
     pub timestamp: f64,
 }
 
-#[serde(rename_all = "camelCase")]
+pub type Bid = BookLevel;
+pub type Ask = BookLevel;
+
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Trade {
     pub price: f64,
     pub size: f64,
@@ -91,40 +351,106 @@ pub struct Trade {
     pub timestamp: f64,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct SystemEvent {
     pub system_event: String,
     pub timestamp: f64,
 }
 
+code_enum! {
+    /// Cause of a trading halt or pause, from the `reason` of a
+    /// `TradingStatus`. Several IEX codes map to the same cause, e.g. `T1` and
+    /// `T2` for news.
+    pub enum HaltReason {
+        /// Limit Up-Limit Down volatility pause (`LUDP`, `LUDS`, `M`).
+        LimitUpLimitDown => "LUDP" | "LUDS" | "M",
+        /// News pending or being disseminated (`T1`, `T2`, `T3`).
+        News => "T1" | "T2" | "T3",
+        /// Order imbalance (`I`).
+        OrderImbalance => "I",
+        /// Operational halt of IEX (`O1`).
+        OperationalHalt => "O1",
+        /// Market-wide circuit breaker (`MCB1` to `MCB3`).
+        CircuitBreaker => "MCB1" | "MCB2" | "MCB3",
+        /// IPO not yet trading or in its order acceptance period (`IPO1`,
+        /// `IPO2`, `IPO3`, `IPOD`).
+        Ipo => "IPO1" | "IPO2" | "IPO3" | "IPOD",
+        /// No reason, sent as `NA` while trading.
+        None => "NA" | "",
+    }
+}
+
+/// Trading status of a security, as sent by the DEEP `tradingstatus`
+/// message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+pub struct TradingStatus {
+    /// `H` halted, `O` order acceptance period, `P` paused or `T` trading.
+    pub status: String,
+    pub reason: HaltReason,
+    pub timestamp: f64,
+}
+
+impl TradingStatus {
+    /// Returns true if trading is halted or paused.
+    pub fn is_halted(&self) -> bool {
+        self.status == "H" || self.status == "P"
+    }
+}
+
+/// Quote, order book and recent trades of a symbol, as returned by
+/// `/stock/{symbol}/book`.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct Book {
+#[serde(rename_all = "camelCase")]
+pub struct StockBook {
     pub quote: Quote,
-    pub bids: Vec<Bid>,
-    pub asks: Vec<Ask>,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
     pub trades: Vec<Trade>,
     pub system_event: SystemEvent,
 }
 
-#[serde(rename_all = "camelCase")]
+pub type Book = StockBook;
+
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Company {
     pub symbol: String,
     pub company_name: String,
     pub exchange: String,
+    /// Industry, with its whitespace normalized like `sector`.
+    #[serde(deserialize_with = "from_collapsed_str")]
     pub industry: String,
     pub website: String,
     pub description: String,
     #[serde(rename = "CEO")]
     pub ceo: String,
     pub issue_type: String,
+    /// Sector, trimmed and with runs of whitespace collapsed, since IEX
+    /// sends the same sector with varying spacing.
+    #[serde(deserialize_with = "from_collapsed_str")]
     pub sector: String,
+    /// Sector, industry and other classification tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Company {
+    /// Returns true if one of the tags of the company is `tag`, ignoring
+    /// case.
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Returns true if the company is in `sector`, ignoring case.
+    pub fn is_in_sector(&self, sector: &str) -> bool {
+        self.sector.eq_ignore_ascii_case(sector)
+    }
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct DelayedQuote {
     pub symbol: String,
     pub delayed_price: f64,
@@ -136,23 +462,35 @@ pub struct DelayedQuote {
     pub processed_time: f64,
 }
 
-#[serde(rename_all = "camelCase")]
+code_enum! {
+    /// Kind of a dividend, from the `flag` of a `Dividend`.
+    pub enum DividendFlag {
+        Cash => "Cash",
+        Stock => "Stock",
+        Special => "Special",
+        /// No flag, sent as an empty string.
+        Unspecified => "",
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Dividend {
     pub ex_date: String,
     pub payment_date: String,
     pub record_date: String,
     pub declared_date: String,
-    pub amount: f64,
-    pub flag: String,
+    #[serde(deserialize_with = "from_money", serialize_with = "to_money")]
+    pub amount: Money,
+    pub flag: DividendFlag,
     #[serde(rename = "type")]
     pub dtype: String, // because "type" is a keyword
     pub qualified: String,
     pub indicated: String,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Earning {
     #[serde(rename = "actualEPS")]
     pub actual_eps: f64,
@@ -170,26 +508,100 @@ pub struct Earning {
     pub fiscal_end_date: String,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Earnings {
     pub symbol: String,
     pub earnings: Vec<Earning>,
 }
 
+/// Earnings report of a company reporting today, see `Client::earnings_today`.
+/// EPS values are `None` until known, e.g. the actual EPS before the report.
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+pub struct TodayEarning {
+    pub symbol: String,
+    #[serde(rename = "actualEPS", default)]
+    pub actual_eps: Option<f64>,
+    #[serde(rename = "consensusEPS", default)]
+    pub consensus_eps: Option<f64>,
+    #[serde(rename = "estimatedEPS", default)]
+    pub estimated_eps: Option<f64>,
+    pub announce_time: String,
+    #[serde(default)]
+    pub number_of_estimates: Option<f64>,
+    #[serde(rename = "EPSReportDate")]
+    pub eps_report_date: String,
+    pub fiscal_period: String,
+    pub fiscal_end_date: String,
+}
+
+/// IPO of the calendar returned by `Client::upcoming_ipos` and
+/// `Client::today_ipos`. Pricing details are `None` until IEX knows them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IpoEntry {
+    pub symbol: String,
+    pub company_name: String,
+    pub expected_date: NaiveDate,
+    #[serde(default)]
+    pub price_low: Option<f64>,
+    #[serde(default)]
+    pub price_high: Option<f64>,
+    /// Amount of the offering in dollars.
+    #[serde(default)]
+    pub offer_amount: Option<f64>,
+    /// Number of shares offered.
+    #[serde(rename = "sharesOffered", default)]
+    pub shares: Option<u64>,
+    /// Filing status, e.g. `Filed` or `Priced`.
+    #[serde(default)]
+    pub status: String,
+}
+
+/// IPO calendar as sent by IEX: the entries in `rawData`, and the same
+/// entries preformatted for display in `viewData`, which is kept untyped so
+/// that strict deserialization doesn't report it.
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IpoCalendar {
+    #[serde(default)]
+    pub raw_data: Vec<IpoEntry>,
+    #[serde(default)]
+    view_data: serde_json::Value,
+}
+
+/// Companies reporting earnings today, as returned by
+/// `/stock/market/today-earnings`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TodayEarnings {
+    /// Reporting before the open (`bto`).
+    #[serde(rename = "bto", default)]
+    pub before_open: Vec<TodayEarning>,
+    /// Reporting after the close (`amc`).
+    #[serde(rename = "amc", default)]
+    pub after_close: Vec<TodayEarning>,
+}
+
+/// Effective spread statistics of a venue. The spread and price improvement
+/// values, tiny fractions of a dollar, are sent as numbers or, by some venues,
+/// as strings in scientific notation (e.g. `"1.23e-4"`).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct EffectiveSpread {
     pub volume: f64,
     pub venue: String,
     pub venue_name: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
     pub effective_spread: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
     pub effective_quoted: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
     pub price_improvement: f64,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Financial {
     pub report_date: String,
     pub gross_profit: f64,
@@ -212,15 +624,15 @@ pub struct Financial {
     pub cash_flow: f64,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Financials {
     pub symbol: String,
     pub financials: Vec<Financial>,
 }
 
-#[serde(rename_all = "PascalCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
 pub struct IEXRegulationSHOThresholdSecurity {
     pub trade_date: String,
     #[serde(rename = "SymbolinINETSymbology")]
@@ -232,8 +644,8 @@ pub struct IEXRegulationSHOThresholdSecurity {
     pub security_name: String,
 }
 
-#[serde(rename_all = "PascalCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
 pub struct IEXShortInterest {
     pub settlement_date: String,
     #[serde(rename = "SymbolinINETSymbology")]
@@ -254,8 +666,8 @@ pub struct IEXShortInterest {
     pub revision_flag: String,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Stats {
     pub company_name: String,
     pub marketcap: f64,
@@ -280,7 +692,7 @@ pub struct Stats {
     pub number_of_estimates: f64,
     #[serde(rename = "EPSSurprisePercent")]
     pub eps_surprise_percent: f64,
-    #[serde(rename = "EPSSurpriseDollar")]
+    #[serde(rename = "EPSSurpriseDollar", default)]
     pub eps_surprise_dollar: Option<f64>,
     pub symbol: String,
     #[serde(rename = "EBITDA")]
@@ -291,6 +703,7 @@ pub struct Stats {
     pub debt: f64,
     #[serde(rename = "ttmEPS")]
     pub ttm_eps: f64,
+    #[serde(default)]
     pub return_on_capital: Option<f64>,
     pub revenue_per_share: f64,
     pub revenue_per_employee: f64,
@@ -316,14 +729,14 @@ pub struct Stats {
     pub day30_change_percent: f64,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Logo {
     pub url: String,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct News {
     pub datetime: String,
     pub headline: String,
@@ -333,24 +746,26 @@ pub struct News {
     pub related: String,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Price {
     pub price: f64,
     pub time: f64,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct OHLC {
     pub open: Price,
     pub close: Price,
+    #[serde(default)]
     pub high: Option<f64>,
+    #[serde(default)]
     pub low: Option<f64>,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Previous {
     pub symbol: String,
     pub date: String,
@@ -365,15 +780,15 @@ pub struct Previous {
     pub vwap: f64,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Relevant {
     pub peers: bool,
     pub symbols: Vec<String>,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Split {
     pub ex_date: String,
     pub declared_date: String,
@@ -384,8 +799,8 @@ pub struct Split {
     pub for_factor: f64,
 }
 
-#[serde(rename_all = "camelCase")]
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct ChartDataPoint {
     pub date: String,
     pub open: f64,
@@ -399,10 +814,53 @@ pub struct ChartDataPoint {
     pub vwap: f64,
     pub label: String,
     pub change_over_time: f64,
+    /// Close adjusted for later splits and dividends, see
+    /// `Client::adjusted_chart`. Not sent by IEX.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjusted_close: Option<f64>,
 }
 
+/// Minute bar of an intraday (`1d`) chart. The IEX trading figures are only
+/// present for minutes of the regular session with IEX trades, so they are
+/// `None` for pre-market and after-hours minutes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+pub struct IntradayBar {
+    /// Trading day, `YYYYMMDD`.
+    pub date: String,
+    /// Minute of the bar in Eastern Time, `HH:MM`.
+    pub minute: String,
+    pub label: String,
+    #[serde(default)]
+    pub open: Option<f64>,
+    #[serde(default)]
+    pub high: Option<f64>,
+    #[serde(default)]
+    pub low: Option<f64>,
+    #[serde(default)]
+    pub close: Option<f64>,
+    /// Average price of the IEX trades of the minute.
+    #[serde(default)]
+    pub average: Option<f64>,
+    #[serde(default)]
+    pub volume: f64,
+    /// Dollar value traded on IEX during the minute.
+    #[serde(default)]
+    pub notional: Option<f64>,
+    #[serde(default)]
+    pub number_of_trades: Option<u64>,
+}
+
+impl IntradayBar {
+    /// Returns true if the bar is a minute of the regular session, i.e. it
+    /// has an average price, a notional and a number of trades.
+    pub fn is_regular_hours(&self) -> bool {
+        self.average.is_some() && self.notional.is_some() && self.number_of_trades.is_some()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct VolumeByVenue {
     pub volume: f64,
     pub venue: String,
@@ -410,3 +868,300 @@ pub struct VolumeByVenue {
     pub market_percent: f64,
     pub avg_market_percent: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{money, QUOTE};
+    use chrono::TimeZone;
+
+    #[test]
+    fn deserialize_book() {
+        let json_data = format!(
+            r#"{{
+                "quote": {},
+                "bids": [{{"price": 222.7, "size": 100, "timestamp": 1540394712345}}],
+                "asks": [
+                    {{"price": 222.76, "size": 200, "timestamp": 1540394712345}},
+                    {{"price": 222.8, "size": 300, "timestamp": 1540394712000}}
+                ],
+                "trades": [{{
+                    "price": 222.73,
+                    "size": 100,
+                    "tradeId": 517341294,
+                    "isISO": false,
+                    "isOddLot": false,
+                    "isOutsideRegularHours": false,
+                    "isSinglePriceCross": false,
+                    "isTradeThroughExempt": false,
+                    "timestamp": 1540394712345
+                }}],
+                "systemEvent": {{"systemEvent": "R", "timestamp": 1540388000000}}
+            }}"#,
+            QUOTE
+        );
+
+        let book: StockBook = serde_json::from_str(&json_data).unwrap();
+        assert_eq!(book.quote.symbol, "AAPL");
+        assert_eq!(book.bids.len(), 1);
+        assert_eq!(book.asks[1].price, 222.8);
+        assert_eq!(book.trades[0].trade_id, 517341294.0);
+        assert_eq!(book.system_event.system_event, "R");
+    }
+
+    #[test]
+    fn company_tags_and_sector() {
+        let json_data = r#"{
+            "symbol": "AAPL",
+            "companyName": "Apple Inc.",
+            "exchange": "Nasdaq Global Select",
+            "industry": "Computer Hardware",
+            "website": "http://www.apple.com",
+            "description": "Apple Inc is designs, manufactures and markets mobile communication and media devices.",
+            "CEO": "Timothy D. Cook",
+            "issueType": "cs",
+            "sector": "Technology",
+            "tags": ["Technology", "Consumer Electronics", "Computer Hardware"]
+        }"#;
+
+        let company: Company = serde_json::from_str(json_data).unwrap();
+        assert!(company.matches_tag("consumer electronics"));
+        assert!(company.matches_tag("COMPUTER HARDWARE"));
+        assert!(!company.matches_tag("Consumer"));
+        assert!(company.is_in_sector("technology"));
+        assert!(!company.is_in_sector("Healthcare"));
+
+        let mut json_data: serde_json::Value = serde_json::from_str(json_data).unwrap();
+        json_data.as_object_mut().unwrap().remove("tags");
+        let company: Company = serde_json::from_value(json_data).unwrap();
+        assert!(company.tags.is_empty());
+    }
+
+    #[test]
+    fn company_sector_and_industry_are_normalized() {
+        let company = |sector: &str, industry: &str| -> Company {
+            serde_json::from_value(serde_json::json!({
+                "symbol": "AAPL",
+                "companyName": "Apple Inc.",
+                "exchange": "Nasdaq Global Select",
+                "industry": industry,
+                "website": "http://www.apple.com",
+                "description": "",
+                "CEO": "Timothy D. Cook",
+                "issueType": "cs",
+                "sector": sector
+            }))
+            .unwrap()
+        };
+        let messy = company("  Consumer \t Goods ", "Computer\n  Hardware");
+        assert_eq!(messy.sector, "Consumer Goods");
+        assert_eq!(messy.industry, "Computer Hardware");
+        assert_eq!(messy.sector, company("Consumer Goods", "").sector);
+    }
+
+    #[test]
+    fn quote_missing_and_null_options() {
+        let mut json_data: serde_json::Value = serde_json::from_str(QUOTE).unwrap();
+        let obj = json_data.as_object_mut().unwrap();
+        obj.remove("peRatio");
+        obj.remove("low");
+        obj.insert("high".to_string(), serde_json::Value::Null);
+
+        let quote: Quote = serde_json::from_value(json_data).unwrap();
+        assert_eq!(quote.pe_ratio, None);
+        assert_eq!(quote.high, None);
+        assert_eq!(quote.low, None);
+    }
+
+    #[test]
+    fn ohlc_missing_and_null_options() {
+        let json_data = r#"{
+            "open": {"price": 221.5, "time": 1540388000000},
+            "close": {"price": 222.73, "time": 1540411200000},
+            "high": null
+        }"#;
+
+        let ohlc: OHLC = serde_json::from_str(json_data).unwrap();
+        assert_eq!(ohlc.high, None);
+        assert_eq!(ohlc.low, None);
+    }
+
+    #[test]
+    fn quote_age() {
+        let quote: Quote = serde_json::from_str(QUOTE).unwrap();
+        let now = Utc.timestamp_millis_opt(1540394712345 + 3_500).unwrap();
+        assert_eq!(quote.age(now), Duration::milliseconds(3_500));
+        assert!(!quote.is_stale(now, Duration::seconds(5)));
+        assert!(quote.is_stale(now, Duration::seconds(3)));
+    }
+
+    #[test]
+    fn quote_display() {
+        let quote: Quote = serde_json::from_str(QUOTE).unwrap();
+        assert_eq!(quote.to_string(), "AAPL 222.73 +1.23 (+0.56%) vol 32112345");
+    }
+
+    #[test]
+    fn quote_change_direction() {
+        let mut quote: Quote = serde_json::from_str(QUOTE).unwrap();
+        assert_eq!(quote.change_direction(), Ordering::Greater);
+        quote.change = money("-0.5");
+        assert_eq!(quote.change_direction(), Ordering::Less);
+        quote.change = money("0");
+        assert_eq!(quote.change_direction(), Ordering::Equal);
+    }
+
+    #[test]
+    fn quote_spread_and_mid() {
+        let mut quote: Quote = serde_json::from_str(QUOTE).unwrap();
+        assert!((quote.spread().unwrap() - 0.06).abs() < 1e-9);
+        assert!((quote.mid().unwrap() - 222.73).abs() < 1e-9);
+
+        // Closed market.
+        quote.iex_bid_price = money("0");
+        quote.iex_ask_price = money("0");
+        assert_eq!(quote.spread(), None);
+        assert_eq!(quote.mid(), None);
+    }
+
+    #[test]
+    fn quote_position_in_52w_range() {
+        let mut quote: Quote = serde_json::from_str(QUOTE).unwrap();
+        quote.week52_low = money("100");
+        quote.week52_high = money("200");
+        quote.latest_price = money("150");
+        assert_eq!(quote.position_in_52w_range(), Some(0.5));
+        quote.latest_price = money("100");
+        assert_eq!(quote.position_in_52w_range(), Some(0.0));
+
+        quote.week52_high = money("100");
+        assert_eq!(quote.position_in_52w_range(), None);
+        quote.week52_low = money("0");
+        quote.week52_high = money("0");
+        assert_eq!(quote.position_in_52w_range(), None);
+    }
+
+    #[test]
+    fn dividend_flag() {
+        let flags: Vec<DividendFlag> =
+            serde_json::from_str(r#"["Cash", "Stock", "Special", "", "FI"]"#).unwrap();
+        assert_eq!(
+            flags,
+            vec![
+                DividendFlag::Cash,
+                DividendFlag::Stock,
+                DividendFlag::Special,
+                DividendFlag::Unspecified,
+                DividendFlag::Other("FI".to_string()),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&flags).unwrap(),
+            r#"["Cash","Stock","Special","","FI"]"#
+        );
+    }
+
+    #[test]
+    fn halt_reason() {
+        let reasons: Vec<HaltReason> =
+            serde_json::from_str(r#"["LUDP", "T2", "I", "O1", "MCB3", "NA", "H10"]"#).unwrap();
+        assert_eq!(
+            reasons,
+            vec![
+                HaltReason::LimitUpLimitDown,
+                HaltReason::News,
+                HaltReason::OrderImbalance,
+                HaltReason::OperationalHalt,
+                HaltReason::CircuitBreaker,
+                HaltReason::None,
+                HaltReason::Other("H10".to_string()),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&HaltReason::Other("H10".to_string())).unwrap(),
+            r#""H10""#
+        );
+
+        let status: TradingStatus =
+            serde_json::from_str(r#"{"status":"T","reason":"NA","timestamp":1}"#).unwrap();
+        assert!(!status.is_halted());
+    }
+
+    #[test]
+    fn intraday_bar_regular_hours() {
+        let json_data = r#"[
+            {"date": "20171215", "minute": "08:15", "label": "08:15 AM",
+             "volume": 0, "average": null},
+            {"date": "20171215", "minute": "09:30", "label": "09:30 AM",
+             "high": 143.98, "low": 143.775, "open": 143.98, "close": 143.775,
+             "average": 143.889, "volume": 3070, "notional": 441740.275,
+             "numberOfTrades": 20, "marketAverage": 143.889}
+        ]"#;
+
+        let bars: Vec<IntradayBar> = serde_json::from_str(json_data).unwrap();
+        assert!(!bars[0].is_regular_hours());
+        assert_eq!(bars[0].high, None);
+        assert!(bars[1].is_regular_hours());
+        assert_eq!(bars[1].number_of_trades, Some(20));
+        assert_eq!(bars[1].notional, Some(441740.275));
+    }
+
+    #[test]
+    fn effective_spread_scientific_notation() {
+        let spread: EffectiveSpread = serde_json::from_str(
+            r#"{
+                "volume": 4899,
+                "venue": "XCHI",
+                "venueName": "CHX",
+                "effectiveSpread": "1.23e-4",
+                "effectiveQuoted": 0.02,
+                "priceImprovement": "-5E-5"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(spread.effective_spread, 0.000123);
+        assert_eq!(spread.effective_quoted, 0.02);
+        assert_eq!(spread.price_improvement, -0.00005);
+    }
+
+    #[test]
+    fn crypto_quote() {
+        let json_data = r#"{
+            "symbol": "BTCUSDT",
+            "companyName": "Bitcoin USD",
+            "primaryExchange": "crypto",
+            "sector": "cryptocurrency",
+            "calculationPrice": "realtime",
+            "open": 6430.1,
+            "openTime": 1537799400000,
+            "close": null,
+            "closeTime": null,
+            "high": 6485.15,
+            "low": 6400,
+            "latestPrice": 6441.78342,
+            "latestSource": "Real time price",
+            "latestTime": "10:32:43 AM",
+            "latestUpdate": 1537799563424,
+            "latestVolume": 2310.15672,
+            "iexRealtimePrice": null,
+            "previousClose": 6425,
+            "change": 16.78342,
+            "changePercent": 0.00261,
+            "marketCap": null,
+            "peRatio": null,
+            "bidPrice": 6441.76,
+            "bidSize": 0.05,
+            "askPrice": 6444.21,
+            "askSize": 2.14
+        }"#;
+
+        let quote: CryptoQuote = serde_json::from_str(json_data).unwrap();
+        assert_eq!(quote.symbol, "BTCUSDT");
+        assert_eq!(quote.latest_price, money("6441.78342"));
+        assert_eq!(quote.close, None);
+        assert_eq!(quote.low, Some(money("6400")));
+        assert_eq!(quote.bid_price, money("6441.76"));
+        assert_eq!(quote.ask_size, 2.14);
+        assert_eq!(quote.latest_volume, Some(2310.15672));
+    }
+}